@@ -1,23 +1,38 @@
 use duckdb::{
-    core::{DataChunkHandle, Inserter, LogicalTypeHandle, LogicalTypeId},
+    core::{DataChunkHandle, FlatVector, Inserter, ListVector, LogicalTypeHandle, LogicalTypeId},
     duckdb_entrypoint_c_api,
     types::DuckString,
     vscalar::{ScalarFunctionSignature, VScalar},
-    vtab::{arrow::WritableVector, BindInfo, InitInfo, TableFunctionInfo, VTab},
+    vtab::{arrow::WritableVector, BindInfo, InitInfo, TableFunctionInfo, VTab, Value},
     Connection, Result,
 };
 use libduckdb_sys::duckdb_string_t;
 use std::{
+    collections::{hash_map::RandomState, BTreeSet, HashMap, HashSet},
     error::Error,
-    net::{IpAddr, Ipv4Addr},
+    hash::{BuildHasher, Hasher},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     str::FromStr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
-use hickory_proto::rr::RecordType;
+use futures::stream::{FuturesUnordered, StreamExt};
+use hickory_proto::op::{Edns, Message, MessageType, OpCode, Query, ResponseCode};
+use hickory_proto::dnssec::Proof;
+use hickory_proto::rr::{domain::Label, rdata::TXT, Name, RData, Record, RecordType};
+use hickory_proto::xfer::Protocol;
+use hickory_proto::ProtoErrorKind;
 use hickory_resolver::config::*;
+use hickory_resolver::lookup::{Ipv4Lookup, Ipv6Lookup, Lookup, MxLookup, NsLookup, ReverseLookup, SoaLookup, SrvLookup, TxtLookup};
+use hickory_resolver::lookup_ip::LookupIp;
 use hickory_resolver::name_server::TokioConnectionProvider;
-use hickory_resolver::Resolver;
+use hickory_resolver::{Hosts, ResolveError, Resolver};
 use once_cell::sync::Lazy;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
 use arc_swap::ArcSwap;
 
 /// Global DNS resolver state shared across all function invocations
@@ -35,11 +50,36 @@ static GLOBAL_DNS_STATE: Lazy<DnsResolverState> = Lazy::new(|| {
 /// The resolver is wrapped in an ArcSwap for lock-free reads with atomic updates.
 /// The concurrency semaphore limits the number of concurrent DNS requests.
 /// The cache size determines how many DNS query results are cached.
+/// The preset and protocol record how the current resolver configuration was built.
+/// The IPv6 zone id is appended to link-local addresses in AAAA results.
+/// Resolvers built from per-call resolver specs are cached by spec string, least recently used first out.
+/// The cache statistics track hits and misses of the session resolver's cache.
+/// The IP filter lists networks whose addresses are dropped from lookup results.
+/// The search order decides whether bare names are tried before search domains.
+/// The hosts source decides which static host entries win over DNS.
+/// Copies of the session resolver restricted to one transport are cached by protocol.
+/// The negative cache TTL, if set, replaces the TTL of cached NXDOMAIN and empty answers.
+/// The address family decides which addresses `dns_lookup` and `dns_lookup_all` return.
 struct DnsResolverState {
     runtime: tokio::runtime::Runtime,
     resolver: ArcSwap<Resolver<TokioConnectionProvider>>,
     concurrency_semaphore: ArcSwap<Arc<tokio::sync::Semaphore>>,
     cache_size: ArcSwap<usize>,
+    preset: ArcSwap<String>,
+    protocol: ArcSwap<DnsProtocol>,
+    ipv6_zone_id: ArcSwap<Option<String>>,
+    spec_resolvers: Mutex<ResolverCache>,
+    cache_stats: CacheStats,
+    ip_filter: ArcSwap<Vec<Cidr>>,
+    search_order: ArcSwap<SearchOrder>,
+    hosts: ArcSwap<HostsSource>,
+    transport_resolvers: Mutex<HashMap<Protocol, SharedResolver>>,
+    negative_cache_ttl: ArcSwap<Option<Duration>>,
+    dual_stack: Mutex<Option<SharedResolver>>,
+    validating: Mutex<Option<SharedResolver>>,
+    query_timeout: ArcSwap<Duration>,
+    attempts: ArcSwap<usize>,
+    address_family: ArcSwap<AddressFamily>,
 }
 
 impl Default for DnsResolverState {
@@ -52,6 +92,8 @@ impl Default for DnsResolverState {
         let cache_size = 4096;
         let mut opts = ResolverOpts::default();
         opts.cache_size = cache_size;
+        opts.use_hosts_file = ResolveHosts::Always;
+        opts.edns0 = true;
         
         let resolver = ArcSwap::from_pointee(
             Resolver::builder_with_config(
@@ -71,36 +113,148 @@ impl Default for DnsResolverState {
             resolver, 
             concurrency_semaphore,
             cache_size: cache_size_atomic,
+            preset: ArcSwap::from_pointee("default".to_string()),
+            protocol: ArcSwap::from_pointee(DnsProtocol::Udp),
+            ipv6_zone_id: ArcSwap::from_pointee(None),
+            spec_resolvers: Mutex::new(ResolverCache::new(SPEC_RESOLVER_CACHE_LIMIT)),
+            cache_stats: CacheStats::default(),
+            ip_filter: ArcSwap::from_pointee(Vec::new()),
+            search_order: ArcSwap::from_pointee(SearchOrder::Ndots(DEFAULT_NDOTS)),
+            hosts: ArcSwap::from_pointee(HostsSource::System),
+            transport_resolvers: Mutex::new(HashMap::new()),
+            negative_cache_ttl: ArcSwap::from_pointee(None),
+            dual_stack: Mutex::new(None),
+            validating: Mutex::new(None),
+            query_timeout: ArcSwap::from_pointee(ResolverOpts::default().timeout),
+            attempts: ArcSwap::from_pointee(ResolverOpts::default().attempts),
+            address_family: ArcSwap::from_pointee(AddressFamily::Ipv4),
         }
     }
 }
 
 impl DnsResolverState {
-    /// Creates a new DnsResolverState with a Tokio runtime and DNS resolver
-    fn new() -> std::result::Result<Self, Box<dyn Error>> {
-        Ok(Self::default())
-    }
-
     /// Updates the resolver configuration
     ///
     /// Creates a new resolver with the specified configuration and atomically
     /// replaces the existing resolver. This clears the DNS cache.
     /// This operation is lock-free and extremely fast.
     fn update_config(&self, config: ResolverConfig) -> std::result::Result<(), Box<dyn Error>> {
-        // Use the stored cache size preference
+        // Use the stored cache size and search order preferences
         let cache_size = **self.cache_size.load();
         let mut opts = ResolverOpts::default();
         opts.cache_size = cache_size;
-        
-        let new_resolver = Resolver::builder_with_config(
-            config,
-            TokioConnectionProvider::default(),
-        )
-        .with_options(opts)
-        .build();
+        opts.ndots = self.search_order.load().ndots();
+        opts.timeout = **self.query_timeout.load();
+        opts.attempts = **self.attempts.load();
+        let new_resolver = self.session_resolver(config, opts);
 
         // Atomic swap - lock-free operation
         self.resolver.store(Arc::new(new_resolver));
+        self.resolver_replaced();
+        Ok(())
+    }
+
+    /// Switches the resolver to a named preset, keeping the current protocol
+    ///
+    /// This clears the DNS cache.
+    fn set_dns_preset(&self, preset: &str) -> std::result::Result<(), Box<dyn Error>> {
+        let config = preset_config(preset, **self.protocol.load())?;
+        self.update_config(config)?;
+        self.preset.store(Arc::new(preset.to_string()));
+        Ok(())
+    }
+
+    /// Switches the transport protocol used to reach the current preset's servers
+    ///
+    /// This clears the DNS cache.
+    fn set_dns_protocol(&self, protocol: DnsProtocol) -> std::result::Result<(), Box<dyn Error>> {
+        let config = preset_config(&self.preset.load(), protocol)?;
+        self.update_config(config)?;
+        self.protocol.store(Arc::new(protocol));
+        Ok(())
+    }
+
+    /// Updates the zone id appended to link-local IPv6 addresses
+    ///
+    /// `None` returns link-local addresses unscoped. A zone id must be an interface
+    /// name or index made of ASCII letters, digits, '.', '_' and '-'.
+    fn set_dns_ipv6_zone_id(&self, zone_id: Option<String>) -> std::result::Result<(), Box<dyn Error>> {
+        if let Some(zone_id) = &zone_id {
+            let valid = zone_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+            if !valid {
+                return Err(format!("Invalid IPv6 zone id '{}'", zone_id).into());
+            }
+        }
+        self.ipv6_zone_id.store(Arc::new(zone_id));
+        Ok(())
+    }
+
+    /// Updates the order in which bare names and search domains are tried
+    ///
+    /// Rebuilds the resolver with the current configuration. This clears the DNS cache.
+    fn set_dns_search_order(&self, order: SearchOrder) -> std::result::Result<(), Box<dyn Error>> {
+        let current = self.resolver.load();
+        let mut opts = current.options().clone();
+        opts.ndots = order.ndots();
+        let new_resolver = self.session_resolver(current.config().clone(), opts);
+
+        self.search_order.store(Arc::new(order));
+        self.resolver.store(Arc::new(new_resolver));
+        self.resolver_replaced();
+        Ok(())
+    }
+
+    /// Updates the static host entries consulted before DNS
+    ///
+    /// Rebuilds the resolver with the current configuration. This clears the DNS cache.
+    fn set_dns_hosts(&self, source: HostsSource) -> std::result::Result<(), Box<dyn Error>> {
+        self.hosts.store(Arc::new(source));
+        let current = self.resolver.load();
+        let new_resolver = self.session_resolver(current.config().clone(), current.options().clone());
+        self.resolver.store(Arc::new(new_resolver));
+        self.resolver_replaced();
+        Ok(())
+    }
+
+    /// Updates how long NXDOMAIN and empty answers are cached
+    ///
+    /// Rebuilds the resolver with the current configuration. This clears the DNS cache.
+    fn set_dns_negative_cache_ttl(&self, ttl: Duration) -> std::result::Result<(), Box<dyn Error>> {
+        self.negative_cache_ttl.store(Arc::new(Some(ttl)));
+        let current = self.resolver.load();
+        let new_resolver = self.session_resolver(current.config().clone(), current.options().clone());
+        self.resolver.store(Arc::new(new_resolver));
+        self.resolver_replaced();
+        Ok(())
+    }
+
+    /// Updates how long the resolver waits for a name server to answer a query
+    ///
+    /// Rebuilds the resolver with the current configuration. This clears the DNS cache.
+    fn set_dns_timeout(&self, timeout: Duration) -> std::result::Result<(), Box<dyn Error>> {
+        self.query_timeout.store(Arc::new(timeout));
+        let current = self.resolver.load();
+        let mut opts = current.options().clone();
+        opts.timeout = timeout;
+        let new_resolver = self.session_resolver(current.config().clone(), opts);
+        self.resolver.store(Arc::new(new_resolver));
+        self.resolver_replaced();
+        Ok(())
+    }
+
+    /// Updates how many times the resolver tries a query before giving up
+    ///
+    /// Rebuilds the resolver with the current configuration. This clears the DNS cache.
+    fn set_dns_attempts(&self, attempts: usize) -> std::result::Result<(), Box<dyn Error>> {
+        self.attempts.store(Arc::new(attempts));
+        let current = self.resolver.load();
+        let mut opts = current.options().clone();
+        opts.attempts = attempts;
+        let new_resolver = self.session_resolver(current.config().clone(), opts);
+        self.resolver.store(Arc::new(new_resolver));
+        self.resolver_replaced();
         Ok(())
     }
 
@@ -135,257 +289,8960 @@ impl DnsResolverState {
         let current_config = self.resolver.load().config().clone();
         let mut opts = self.resolver.load().options().clone();
         opts.cache_size = size;
-        
-        let new_resolver = Resolver::builder_with_config(
-            current_config,
-            TokioConnectionProvider::default(),
-        )
-        .with_options(opts)
-        .build();
+        let new_resolver = self.session_resolver(current_config, opts);
         
         // Atomic swap - existing queries continue with old resolver
         self.resolver.store(Arc::new(new_resolver));
+        self.resolver_replaced();
         Ok(())
     }
-}
 
-/// Validates and parses an IPv4 address string
-///
-/// # Arguments
-/// * `ip_str` - A string slice containing an IPv4 address
-///
-/// # Returns
-/// * `Ok(Ipv4Addr)` - Successfully parsed IPv4 address
-/// * `Err` - Invalid IPv4 address format
-fn validate_ipv4(ip_str: &str) -> std::result::Result<Ipv4Addr, Box<dyn Error>> {
-    match Ipv4Addr::from_str(ip_str.trim()) {
-        Ok(addr) => Ok(addr),
-        Err(_) => Err(format!("Invalid IPv4 address format: {}", ip_str).into()),
+    /// Returns the resolver for a per-call resolver spec, building it on first use
+    ///
+    /// Resolvers are cached by spec string so repeated calls share their DNS cache,
+    /// see `ResolverCache`.
+    fn spec_resolver(
+        &self,
+        spec: &str,
+    ) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        self.spec_resolver_with_key(&spec.trim().to_lowercase(), spec)
     }
-}
 
-/// Performs an asynchronous reverse DNS lookup for an IPv4 address
-///
-/// # Arguments
-/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
-/// * `ip_str` - String containing the IPv4 address to resolve
-///
-/// # Returns
-/// * `Ok(String)` - The resolved hostname
-/// * `Err` - Lookup failed or invalid IP address
-async fn reverse_dns_lookup_async(
-    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
-    ip_str: &str,
-) -> std::result::Result<String, Box<dyn Error>> {
-    let ipv4 = validate_ipv4(ip_str)?;
-    let ip_addr = IpAddr::V4(ipv4);
+    /// Returns the resolver for a resolver spec, cached under `key` instead of the spec
+    ///
+    /// Lets equivalent specs, e.g. reorderings of a name server list, share one resolver.
+    fn spec_resolver_with_key(
+        &self,
+        key: &str,
+        spec: &str,
+    ) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        let key = key.to_string();
+        let mut resolvers = self.spec_resolvers.lock().map_err(|_| "Resolver spec cache poisoned")?;
+        if let Some(resolver) = resolvers.get(&key) {
+            return Ok(resolver);
+        }
 
-    // Lock-free load of the current resolver
-    let resolver_guard = resolver.load();
+        let (config, opts) = parse_resolver_spec(spec)?;
+        let resolver = self.shared_resolver(config, opts);
 
-    match resolver_guard.reverse_lookup(ip_addr).await {
-        Ok(lookup) => {
-            // Get the first hostname from the lookup result
-            if let Some(name) = lookup.iter().next() {
-                Ok(name.to_string().trim_end_matches('.').to_string())
-            } else {
-                Err("No hostname found for IP address".into())
+        resolvers.insert(key, resolver.clone());
+        Ok(resolver)
+    }
+
+    /// Returns the resolver for a DNS-over-HTTPS endpoint, building it on first use
+    ///
+    /// Queries are sent over HTTP/2 (`Protocol::Https`), or over HTTP/3 (`Protocol::H3`)
+    /// when the `doh3` Cargo feature is enabled. A host name in the URL is bootstrapped
+    /// through the session resolver. Resolvers share the cache of `spec_resolver`, keyed
+    /// by HTTP version and URL.
+    fn doh_resolver(&self, url: &str, protocol: Protocol) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        let url = url.trim();
+        let (host, port, path) = parse_doh_url(url)?;
+        let key = format!("doh:{}#{}", protocol, url);
+        if let Some(resolver) = self.spec_resolvers.lock().map_err(|_| "Resolver spec cache poisoned")?.get(&key) {
+            return Ok(resolver);
+        }
+
+        let ips: Vec<IpAddr> = match IpAddr::from_str(&host) {
+            Ok(ip) => vec![ip],
+            Err(_) => {
+                let resolver_guard = self.resolver.load();
+                let lookup = self
+                    .runtime
+                    .block_on(resolver_guard.lookup_ip(host.as_str()))
+                    .map_err(|e| format!("Failed to resolve DoH server '{}': {}", host, e))?;
+                lookup.iter().collect()
             }
+        };
+        let name_servers: Vec<NameServerConfig> = ips
+            .into_iter()
+            .map(|ip| {
+                let mut name_server = NameServerConfig::new(SocketAddr::new(ip, port), protocol);
+                name_server.tls_dns_name = Some(host.clone());
+                name_server.http_endpoint = Some(path.clone());
+                name_server.trust_negative_responses = true;
+                name_server
+            })
+            .collect();
+        let config = ResolverConfig::from_parts(None, vec![], NameServerConfigGroup::from(name_servers));
+        let resolver = self.shared_resolver(config, ResolverOpts::default());
+
+        let mut resolvers = self.spec_resolvers.lock().map_err(|_| "Resolver spec cache poisoned")?;
+        resolvers.insert(key, resolver.clone());
+        Ok(resolver)
+    }
+
+    /// Returns the resolver for a DNS-over-TLS server, building it on first use
+    ///
+    /// The server certificate is validated against `tls_name`. Resolvers share the cache
+    /// of `spec_resolver`.
+    fn dot_resolver(&self, server: &str, tls_name: &str) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        let server = server.trim();
+        let tls_name = tls_name.trim();
+        let socket_addr = SocketAddr::from_str(server)
+            .or_else(|_| IpAddr::from_str(server).map(|ip| SocketAddr::new(ip, 853)))
+            .map_err(|_| format!("Invalid DoT server '{}': expected an IP address with optional port", server))?;
+        if tls_name.is_empty() {
+            return Err("DoT TLS name must not be empty".into());
         }
-        Err(e) => Err(format!("Reverse DNS lookup failed: {}", e).into()),
+
+        let key = format!("dot:{}#{}", socket_addr, tls_name.to_lowercase());
+        let mut resolvers = self.spec_resolvers.lock().map_err(|_| "Resolver spec cache poisoned")?;
+        if let Some(resolver) = resolvers.get(&key) {
+            return Ok(resolver);
+        }
+
+        let mut name_server = NameServerConfig::new(socket_addr, Protocol::Tls);
+        name_server.tls_dns_name = Some(tls_name.to_string());
+        let config = ResolverConfig::from_parts(None, vec![], NameServerConfigGroup::from(vec![name_server]));
+        let resolver = self.shared_resolver(config, ResolverOpts::default());
+
+        resolvers.insert(key, resolver.clone());
+        Ok(resolver)
     }
-}
 
-/// Performs an asynchronous forward DNS lookup, returning the first IPv4 address
-///
-/// # Arguments
-/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
-/// * `hostname` - String containing the hostname to resolve
-///
-/// # Returns
-/// * `Ok(String)` - The first IPv4 address found
-/// * `Err` - No IPv4 addresses found or lookup failed
-async fn dns_lookup_async(
-    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
-    hostname: &str,
-) -> std::result::Result<String, Box<dyn Error>> {
-    let hostname = hostname.trim();
+    /// Builds a shareable resolver with the session's cache size
+    fn shared_resolver(&self, config: ResolverConfig, mut opts: ResolverOpts) -> SharedResolver {
+        opts.cache_size = **self.cache_size.load();
+        opts.edns0 = true;
+        Arc::new(ArcSwap::from_pointee(
+            Resolver::builder_with_config(config, TokioConnectionProvider::default())
+                .with_options(opts)
+                .build(),
+        ))
+    }
 
-    // Lock-free load of the current resolver
-    let resolver_guard = resolver.load();
+    /// Empties the DNS cache of the session resolver and of every cached per-call resolver
+    ///
+    /// Resolvers derived from the session resolver are dropped and rebuilt on next use,
+    /// per-call resolvers stay cached with empty DNS caches.
+    fn clear_dns_cache(&self) -> std::result::Result<(), Box<dyn Error>> {
+        self.resolver.load().clear_cache();
+        self.resolver_replaced();
+        self.spec_resolvers.lock().map_err(|_| "Resolver spec cache poisoned")?.clear_dns_caches();
+        Ok(())
+    }
 
-    match resolver_guard.lookup_ip(hostname).await {
-        Ok(lookup) => {
-            // Find the first IPv4 address
-            for ip in lookup.iter() {
-                if let IpAddr::V4(ipv4) = ip {
-                    return Ok(ipv4.to_string());
-                }
-            }
-            Err("No IPv4 addresses found for hostname".into())
+    /// Drops state derived from the previous session resolver after it was replaced
+    fn resolver_replaced(&self) {
+        self.cache_stats.clear_entries();
+        if let Ok(mut resolvers) = self.spec_resolvers.lock() {
+            resolvers.remove_prefixed(TIMEOUT_RESOLVER_KEY_PREFIX);
+        }
+        if let Ok(mut resolvers) = self.transport_resolvers.lock() {
+            resolvers.clear();
+        }
+        if let Ok(mut resolver) = self.dual_stack.lock() {
+            *resolver = None;
+        }
+        if let Ok(mut resolver) = self.validating.lock() {
+            *resolver = None;
         }
-        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
     }
-}
 
-/// Performs an asynchronous forward DNS lookup, returning all IPv4 addresses
-///
-/// # Arguments
-/// * `resolver` - Reference to the RwLock-wrapped Hickory DNS resolver
-/// * `hostname` - String containing the hostname to resolve
-///
-/// # Returns
-/// * `Ok(Vec<String>)` - All IPv4 addresses found
-/// * `Err` - No IPv4 addresses found or lookup failed
-async fn dns_lookup_all_async(
-    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
-    hostname: &str,
-) -> std::result::Result<Vec<String>, Box<dyn Error>> {
-    let hostname = hostname.trim();
+    /// Returns a copy of the session resolver validating DNSSEC, building it on first use
+    ///
+    /// Answers are validated by the extension against the built-in root trust anchor,
+    /// every record carrying its proof. It is cached until the session resolver is replaced.
+    fn validating_resolver(&self) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        let mut validating = self.validating.lock().map_err(|_| "Validating resolver cache poisoned")?;
+        if let Some(resolver) = &*validating {
+            return Ok(resolver.clone());
+        }
 
-    // Lock-free load of the current resolver
-    let resolver_guard = resolver.load();
+        let current = self.resolver.load();
+        let mut opts = current.options().clone();
+        opts.validate = true;
+        let resolver = Arc::new(ArcSwap::from_pointee(self.session_resolver(current.config().clone(), opts)));
 
-    match resolver_guard.lookup_ip(hostname).await {
-        Ok(lookup) => {
-            let ips: Vec<String> = lookup
-                .iter()
-                .filter_map(|ip| {
-                    // Only return IPv4 addresses
-                    if let IpAddr::V4(ipv4) = ip {
-                        Some(ipv4.to_string())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+        *validating = Some(resolver.clone());
+        Ok(resolver)
+    }
 
-            if ips.is_empty() {
-                Err("No IPv4 addresses found for hostname".into())
-            } else {
-                Ok(ips)
-            }
+    /// Returns a copy of the session resolver querying A and AAAA records, building it on first use
+    ///
+    /// The session resolver only falls back to AAAA when a name has no A records, this
+    /// copy always returns both families. It is cached until the session resolver is replaced.
+    fn dual_stack_resolver(&self) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        let mut dual_stack = self.dual_stack.lock().map_err(|_| "Dual-stack resolver cache poisoned")?;
+        if let Some(resolver) = &*dual_stack {
+            return Ok(resolver.clone());
         }
-        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
-    }
-}
 
-/// Parses a DNS record type string into a RecordType enum
-///
-/// # Arguments
-/// * `record_type_str` - String containing the record type (case-insensitive)
-///
-/// # Supported Types
-/// A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, CAA
-///
-/// # Returns
-/// * `Ok(RecordType)` - Successfully parsed record type
-/// * `Err` - Unsupported or invalid record type
-fn parse_record_type(record_type_str: &str) -> std::result::Result<RecordType, Box<dyn Error>> {
-    let record_type_upper = record_type_str.trim().to_uppercase();
+        let current = self.resolver.load();
+        let mut opts = current.options().clone();
+        opts.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+        let resolver = Arc::new(ArcSwap::from_pointee(self.session_resolver(current.config().clone(), opts)));
 
-    match record_type_upper.as_str() {
-        "A" => Ok(RecordType::A),
-        "AAAA" => Ok(RecordType::AAAA),
-        "CNAME" => Ok(RecordType::CNAME),
-        "MX" => Ok(RecordType::MX),
-        "NS" => Ok(RecordType::NS),
-        "PTR" => Ok(RecordType::PTR),
-        "SOA" => Ok(RecordType::SOA),
-        "SRV" => Ok(RecordType::SRV),
-        "TXT" => Ok(RecordType::TXT),
-        "CAA" => Ok(RecordType::CAA),
-        _ => Err(format!("Unsupported record type: {}", record_type_str).into()),
+        *dual_stack = Some(resolver.clone());
+        Ok(resolver)
     }
-}
-
-/// Performs an asynchronous DNS lookup for a specific record type, returning the first record
-///
-/// # Arguments
-/// * `resolver` - Reference to the RwLock-wrapped Hickory DNS resolver
-/// * `hostname` - String containing the hostname to query
-/// * `record_type` - The DNS record type to query for
-///
-/// # Returns
-/// * `Ok(String)` - The first record of the specified type
-/// * `Err` - No records found or lookup failed
-async fn dns_lookup_with_type_async(
-    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
-    hostname: &str,
-    record_type: RecordType,
-) -> std::result::Result<String, Box<dyn Error>> {
-    let hostname = hostname.trim();
 
-    // Lock-free load of the current resolver
-    let resolver_guard = resolver.load();
+    /// Returns a copy of the session resolver using a single transport, building it on first use
+    ///
+    /// Every plain name server address of the configuration is queried over `protocol`
+    /// only, so TCP is used end-to-end rather than as a fallback for truncated answers.
+    /// Resolvers are cached by protocol until the session resolver is replaced.
+    fn transport_resolver(&self, protocol: Protocol) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        let mut resolvers = self.transport_resolvers.lock().map_err(|_| "Transport resolver cache poisoned")?;
+        if let Some(resolver) = resolvers.get(&protocol) {
+            return Ok(resolver.clone());
+        }
 
-    match resolver_guard.lookup(hostname, record_type).await {
-        Ok(lookup) => {
-            if let Some(record) = lookup.record_iter().next() {
-                Ok(record.data().to_string())
-            } else {
-                Err(format!("No {} records found for hostname", record_type).into())
+        let current = self.resolver.load();
+        let mut addrs: Vec<SocketAddr> = Vec::new();
+        for name_server in current.config().name_servers() {
+            let plain = matches!(name_server.protocol, Protocol::Udp | Protocol::Tcp);
+            if plain && !addrs.contains(&name_server.socket_addr) {
+                addrs.push(name_server.socket_addr);
             }
         }
-        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+        if addrs.is_empty() {
+            return Err("No plain DNS name server configured".into());
+        }
+        let name_servers: Vec<NameServerConfig> = addrs
+            .into_iter()
+            .map(|addr| NameServerConfig::new(addr, protocol))
+            .collect();
+        let config = ResolverConfig::from_parts(
+            current.config().domain().cloned(),
+            current.config().search().to_vec(),
+            NameServerConfigGroup::from(name_servers),
+        );
+        let resolver = Arc::new(ArcSwap::from_pointee(self.session_resolver(config, current.options().clone())));
+
+        resolvers.insert(protocol, resolver.clone());
+        Ok(resolver)
     }
-}
 
-/// Performs an asynchronous DNS lookup for a specific record type, returning all records
-///
-/// # Arguments
-/// * `resolver` - Reference to the RwLock-wrapped Hickory DNS resolver
-/// * `hostname` - String containing the hostname to query
-/// * `record_type` - The DNS record type to query for
-///
-/// # Returns
-/// * `Ok(Vec<String>)` - All records of the specified type
-/// * `Err` - No records found or lookup failed
-async fn dns_lookup_all_with_type_async(
-    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
-    hostname: &str,
-    record_type: RecordType,
-) -> std::result::Result<Vec<String>, Box<dyn Error>> {
-    let hostname = hostname.trim();
+    /// Returns a copy of the session resolver with a per-attempt timeout, building it on first use
+    ///
+    /// Resolvers are cached by timeout in the cache of `spec_resolver` until the session
+    /// resolver is replaced. A single attempt is made per query so the timeout is the
+    /// whole budget of a lookup.
+    fn timeout_resolver(&self, timeout: Duration) -> std::result::Result<SharedResolver, Box<dyn Error>> {
+        let key = format!("{}{}", TIMEOUT_RESOLVER_KEY_PREFIX, timeout.as_millis());
+        let mut resolvers = self.spec_resolvers.lock().map_err(|_| "Resolver spec cache poisoned")?;
+        if let Some(resolver) = resolvers.get(&key) {
+            return Ok(resolver);
+        }
 
-    // Lock-free load of the current resolver
-    let resolver_guard = resolver.load();
+        let current = self.resolver.load();
+        let mut opts = current.options().clone();
+        opts.timeout = timeout;
+        opts.attempts = 1;
+        let resolver = Arc::new(ArcSwap::from_pointee(self.session_resolver(current.config().clone(), opts)));
 
-    match resolver_guard.lookup(hostname, record_type).await {
-        Ok(lookup) => {
-            let records: Vec<String> = lookup
-                .record_iter()
-                .map(|record| record.data().to_string())
-                .collect();
+        resolvers.insert(key, resolver.clone());
+        Ok(resolver)
+    }
 
-            if records.is_empty() {
-                Err(format!("No {} records found for hostname", record_type).into())
-            } else {
-                Ok(records)
-            }
+    /// Builds a resolver that only reaches name servers of one address family
+    ///
+    /// Uses the current configuration and options, keeping only the IPv4 or the
+    /// IPv6 name server addresses. The resolver starts with an empty cache.
+    ///
+    /// # Returns
+    /// * `Some(Resolver)` - The configuration has name servers of the family
+    /// * `None` - No name server of the family is configured
+    fn family_resolver(&self, ipv6: bool) -> Option<Resolver<TokioConnectionProvider>> {
+        let current = self.resolver.load();
+        let name_servers: Vec<NameServerConfig> = current
+            .config()
+            .name_servers()
+            .iter()
+            .filter(|name_server| name_server.socket_addr.is_ipv6() == ipv6)
+            .cloned()
+            .collect();
+        if name_servers.is_empty() {
+            return None;
         }
-        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+
+        let config = ResolverConfig::from_parts(
+            current.config().domain().cloned(),
+            current.config().search().to_vec(),
+            NameServerConfigGroup::from(name_servers),
+        );
+        Some(self.session_resolver(config, current.options().clone()))
+    }
+
+    /// Returns the first plain DNS name server of the current configuration and the query timeout
+    ///
+    /// Used for raw queries that need the complete response message. Encrypted
+    /// name servers (e.g. DNS-over-HTTP/3) are skipped.
+    fn plain_name_server(&self) -> std::result::Result<(SocketAddr, Duration), Box<dyn Error>> {
+        let current = self.resolver.load();
+        current
+            .config()
+            .name_servers()
+            .iter()
+            .find(|name_server| matches!(name_server.protocol, Protocol::Udp | Protocol::Tcp))
+            .map(|name_server| (name_server.socket_addr, current.options().timeout))
+            .ok_or_else(|| "No plain DNS name server configured".into())
+    }
+
+    /// Builds a fresh resolver with the current configuration and an empty cache
+    ///
+    /// Used when every query must reach the upstream servers, e.g. for
+    /// repeated sampling of the same name.
+    fn uncached_resolver(&self) -> Resolver<TokioConnectionProvider> {
+        let current = self.resolver.load();
+        self.session_resolver(current.config().clone(), current.options().clone())
+    }
+
+    /// Builds a resolver consulting the session's hosts source before DNS
+    fn session_resolver(&self, config: ResolverConfig, mut opts: ResolverOpts) -> Resolver<TokioConnectionProvider> {
+        // EDNS lets servers answer large records over UDP instead of truncating them
+        opts.edns0 = true;
+        let negative_cache_ttl = **self.negative_cache_ttl.load();
+        opts.negative_min_ttl = negative_cache_ttl;
+        opts.negative_max_ttl = negative_cache_ttl;
+        let hosts = self.hosts.load();
+        opts.use_hosts_file = match **hosts {
+            HostsSource::System => ResolveHosts::Always,
+            HostsSource::Disabled | HostsSource::File { .. } => ResolveHosts::Never,
+        };
+        let mut resolver = Resolver::builder_with_config(config, TokioConnectionProvider::default())
+            .with_options(opts)
+            .build();
+        if let HostsSource::File { hosts, .. } = &**hosts {
+            resolver.set_hosts(hosts.clone());
+        }
+        resolver
     }
 }
 
-/// Reverse DNS lookup scalar function
-///
-/// Performs reverse DNS lookups by converting IPv4 addresses to hostnames.
-///
-/// # Arguments
-/// * `ip_address` - A VARCHAR containing an IPv4 address (e.g., "8.8.8.8")
+/// Statistics of the session resolver's DNS cache
+///
+/// Hickory does not expose its cache, so the extension mirrors it: every lookup
+/// through the session resolver records the expiry of its answer (or of a negative
+/// answer), and a repeated lookup of the same name and record type before that
+/// expiry counts as a cache hit. The mirror holds at most `cache_size` entries and
+/// evicts the entry expiring soonest when full, like the resolver's own cache.
+#[derive(Default)]
+struct CacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    entries: Mutex<HashMap<(String, RecordType), Instant>>,
+}
+
+/// Point-in-time copy of `CacheStats` as returned by `dns_cache_stats`
+struct CacheStatsSnapshot {
+    entries: i32,
+    hits: i64,
+    misses: i64,
+    evictions: i64,
+    hit_ratio: f64,
+}
+
+impl CacheStats {
+    /// Records a lookup of `name` and `record_type` whose answer is valid until `valid_until`
+    ///
+    /// `valid_until` is `None` for errors that the resolver does not cache.
+    fn record(&self, name: &str, record_type: RecordType, valid_until: Option<Instant>, capacity: usize) {
+        let now = Instant::now();
+        let key = (normalize_name(name), record_type);
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+
+        if entries.get(&key).is_some_and(|expiry| *expiry > now) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let Some(valid_until) = valid_until.filter(|expiry| *expiry > now) else {
+            entries.remove(&key);
+            return;
+        };
+        if !entries.contains_key(&key) && entries.len() >= capacity {
+            entries.retain(|_, expiry| *expiry > now);
+            if entries.len() >= capacity {
+                let soonest = entries.iter().min_by_key(|(_, expiry)| **expiry).map(|(key, _)| key.clone());
+                if let Some(soonest) = soonest {
+                    entries.remove(&soonest);
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        entries.insert(key, valid_until);
+    }
+
+    /// Forgets all cached entries, e.g. after the resolver was rebuilt; counters are kept
+    fn clear_entries(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+
+    /// Returns the current counters and the number of unexpired entries
+    fn snapshot(&self) -> CacheStatsSnapshot {
+        let now = Instant::now();
+        let entries = self
+            .entries
+            .lock()
+            .map_or(0, |entries| entries.values().filter(|expiry| **expiry > now).count());
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        CacheStatsSnapshot {
+            entries: i32::try_from(entries).unwrap_or(i32::MAX),
+            hits: hits as i64,
+            misses: misses as i64,
+            evictions: self.evictions.load(Ordering::Relaxed) as i64,
+            hit_ratio: if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 },
+        }
+    }
+}
+
+/// Resolver answers whose expiry can be tracked by `CacheStats`
+trait CachedAnswer {
+    /// Instant until which the resolver serves this answer from its cache
+    fn valid_until(&self) -> Instant;
+}
+
+impl CachedAnswer for Lookup {
+    fn valid_until(&self) -> Instant {
+        Lookup::valid_until(self)
+    }
+}
+
+impl CachedAnswer for LookupIp {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for Ipv4Lookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for Ipv6Lookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for MxLookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for SoaLookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for SrvLookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for TxtLookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for NsLookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+impl CachedAnswer for ReverseLookup {
+    fn valid_until(&self) -> Instant {
+        self.as_lookup().valid_until()
+    }
+}
+
+/// Awaits a lookup and records it in the cache statistics
+///
+/// Only lookups through the current session resolver are recorded; per-call and
+/// uncached resolvers have caches of their own.
+///
+/// # Arguments
+/// * `resolver` - The resolver performing the lookup
+/// * `name` - The queried name
+/// * `record_type` - The queried record type
+/// * `lookup` - The pending lookup
+async fn tracked<T: CachedAnswer>(
+    resolver: &Resolver<TokioConnectionProvider>,
+    name: &str,
+    record_type: RecordType,
+    lookup: impl std::future::Future<Output = std::result::Result<T, ResolveError>>,
+) -> std::result::Result<T, ResolveError> {
+    let result = lookup.await;
+
+    if std::ptr::eq(resolver, &**GLOBAL_DNS_STATE.resolver.load()) {
+        let valid_until = match &result {
+            Ok(answer) => Some(answer.valid_until()),
+            Err(e) => match e.proto().map(|e| e.kind()) {
+                Some(ProtoErrorKind::NoRecordsFound { negative_ttl: Some(ttl), .. }) => {
+                    Some(Instant::now() + Duration::from_secs(u64::from(*ttl)))
+                }
+                _ => None,
+            },
+        };
+        GLOBAL_DNS_STATE
+            .cache_stats
+            .record(name, record_type, valid_until, **GLOBAL_DNS_STATE.cache_size.load());
+    }
+
+    result
+}
+
+/// A resolver that can be shared across lookups and swapped atomically
+type SharedResolver = Arc<ArcSwap<Resolver<TokioConnectionProvider>>>;
+
+/// Default number of per-call resolvers kept by `ResolverCache`
+const SPEC_RESOLVER_CACHE_LIMIT: usize = 64;
+
+/// Key prefix of the `timeout_resolver` entries in the `ResolverCache`
+const TIMEOUT_RESOLVER_KEY_PREFIX: &str = "timeout:";
+
+/// Bounded least-recently-used cache of per-call resolvers, keyed by configuration
+///
+/// Resolver specs, DoH endpoints and DoT servers that repeat share one resolver and
+/// its DNS cache. Once `capacity` resolvers are cached, inserting another evicts the one
+/// used least recently, so a column of thousands of distinct name servers cannot grow
+/// the cache without bound.
+struct ResolverCache {
+    capacity: usize,
+    clock: u64,
+    entries: HashMap<String, (SharedResolver, u64)>,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl ResolverCache {
+    fn new(capacity: usize) -> Self {
+        ResolverCache {
+            capacity,
+            clock: 0,
+            entries: HashMap::new(),
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Returns the cached resolver for `key`, marking it as most recently used
+    fn get(&mut self, key: &str) -> Option<SharedResolver> {
+        self.clock += 1;
+        match self.entries.get_mut(key) {
+            Some((resolver, last_used)) => {
+                *last_used = self.clock;
+                self.hits += 1;
+                Some(resolver.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Caches `resolver` under `key`, evicting the least recently used resolvers if full
+    fn insert(&mut self, key: String, resolver: SharedResolver) {
+        self.clock += 1;
+        if !self.entries.contains_key(&key) {
+            self.evict_to(self.capacity.saturating_sub(1));
+        }
+        self.entries.insert(key, (resolver, self.clock));
+    }
+
+    /// Changes the capacity, evicting the least recently used resolvers past it
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    /// Drops every cached resolver whose key starts with `prefix`
+    fn remove_prefixed(&mut self, prefix: &str) {
+        self.entries.retain(|key, _| !key.starts_with(prefix));
+    }
+
+    /// Empties the DNS cache of every cached resolver, keeping the resolvers
+    fn clear_dns_caches(&self) {
+        for (resolver, _) in self.entries.values() {
+            resolver.load().clear_cache();
+        }
+    }
+
+    fn evict_to(&mut self, len: usize) {
+        while self.entries.len() > len {
+            let oldest = self.entries.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(key, _)| key.clone());
+            let Some(oldest) = oldest else {
+                return;
+            };
+            self.entries.remove(&oldest);
+            self.evictions += 1;
+        }
+    }
+}
+
+/// Longest per-attempt timeout accepted by the `timeout=` resolver spec option
+const MAX_SPEC_TIMEOUT_SECS: f64 = 3600.0;
+
+/// Parses a resolver spec string into a resolver configuration and options
+///
+/// A spec is a comma-separated list of name servers followed by optional
+/// `;`-separated options, e.g. `1.1.1.1,8.8.8.8;tcp;timeout=2`.
+/// Name servers are IP addresses with an optional port (`9.9.9.9:53`, `[2620:fe::fe]:53`).
+///
+/// # Supported Options
+/// * `udp` / `tcp` - Transport protocol (default: udp)
+/// * `timeout=<seconds>` - Per-attempt timeout, fractions allowed, at most 3600 (default: 5)
+/// * `attempts=<n>` - Attempts per query, 1-10 (default: 2)
+/// * `bind=<ip>` - Local source address queries are sent from, e.g. to pick the egress
+///   interface of a multi-homed host (default: chosen by the operating system)
+///
+/// # Returns
+/// * `Ok((ResolverConfig, ResolverOpts))` - The parsed configuration
+/// * `Err` - Empty server list, invalid address, unusable bind address or unknown option
+fn parse_resolver_spec(spec: &str) -> std::result::Result<(ResolverConfig, ResolverOpts), Box<dyn Error>> {
+    let invalid = |reason: String| -> Box<dyn Error> { format!("Invalid resolver spec '{}': {}", spec, reason).into() };
+
+    let mut parts = spec.split(';').map(str::trim);
+    let servers = parts
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|server| !server.is_empty())
+        .map(|server| {
+            SocketAddr::from_str(server)
+                .or_else(|_| IpAddr::from_str(server).map(|ip| SocketAddr::new(ip, 53)))
+                .map_err(|_| invalid(format!("invalid name server '{}'", server)))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    if servers.is_empty() {
+        return Err(invalid("no name servers given".to_string()));
+    }
+
+    let mut protocol = Protocol::Udp;
+    let mut bind_addr = None;
+    let mut opts = ResolverOpts::default();
+    for option in parts.filter(|option| !option.is_empty()) {
+        let (key, value) = match option.split_once('=') {
+            Some((key, value)) => (key.trim().to_lowercase(), Some(value.trim())),
+            None => (option.to_lowercase(), None),
+        };
+        match (key.as_str(), value) {
+            ("udp", None) => protocol = Protocol::Udp,
+            ("tcp", None) => protocol = Protocol::Tcp,
+            ("timeout", Some(value)) => {
+                let secs = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|secs| secs.is_finite() && *secs > 0.0)
+                    .ok_or_else(|| invalid(format!("timeout must be a positive number of seconds, got '{}'", value)))?;
+                if secs > MAX_SPEC_TIMEOUT_SECS {
+                    return Err(invalid(format!("timeout must be at most {} seconds, got '{}'", MAX_SPEC_TIMEOUT_SECS, value)));
+                }
+                opts.timeout = Duration::from_secs_f64(secs);
+            }
+            ("attempts", Some(value)) => {
+                opts.attempts = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (1..=10).contains(n))
+                    .ok_or_else(|| invalid(format!("attempts must be between 1 and 10, got '{}'", value)))?;
+            }
+            ("bind", Some(value)) => {
+                let ip = IpAddr::from_str(value)
+                    .map_err(|_| invalid(format!("bind must be an IP address, got '{}'", value)))?;
+                if servers.iter().any(|server| server.is_ipv4() != ip.is_ipv4()) {
+                    return Err(invalid(format!("bind address '{}' does not match the address family of every name server", ip)));
+                }
+                // Fail now rather than on every query if the address is not local
+                std::net::UdpSocket::bind(SocketAddr::new(ip, 0))
+                    .map_err(|e| invalid(format!("cannot bind to '{}': {}", ip, e)))?;
+                bind_addr = Some(SocketAddr::new(ip, 0));
+            }
+            _ => return Err(invalid(format!("unknown option '{}'", option))),
+        }
+    }
+
+    let name_servers: Vec<NameServerConfig> = servers
+        .into_iter()
+        .map(|addr| {
+            let mut name_server = NameServerConfig::new(addr, protocol);
+            name_server.bind_addr = bind_addr;
+            name_server
+        })
+        .collect();
+    let config = ResolverConfig::from_parts(None, vec![], NameServerConfigGroup::from(name_servers));
+    Ok((config, opts))
+}
+
+/// Parses a DNS-over-HTTPS endpoint URL such as `https://cloudflare-dns.com/dns-query`
+///
+/// # Returns
+/// * `Ok((host, port, path))` - The server host (a name or an IP address), the port
+///   (default 443) and the request path (default `/dns-query`)
+/// * `Err` - The URL is not an `https://` URL with a valid host and port
+fn parse_doh_url(url: &str) -> std::result::Result<(String, u16, String), Box<dyn Error>> {
+    let invalid = |reason: &str| -> Box<dyn Error> { format!("Invalid DoH URL '{}': {}", url, reason).into() };
+
+    let rest = url
+        .strip_prefix("https://")
+        .ok_or_else(|| invalid("must start with https://"))?;
+    let (authority, path) = match rest.find(['/', '?', '#']) {
+        Some(index) if rest[index..].starts_with('/') => (&rest[..index], &rest[index..]),
+        Some(_) => return Err(invalid("query strings and fragments are not supported")),
+        None => (rest, "/dns-query"),
+    };
+    if path.contains(['?', '#']) {
+        return Err(invalid("query strings and fragments are not supported"));
+    }
+
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        // IPv6 literal, e.g. [2606:4700:4700::1111]:443
+        let (ip, after) = bracketed.split_once(']').ok_or_else(|| invalid("unterminated IPv6 address"))?;
+        Ipv6Addr::from_str(ip).map_err(|_| invalid("invalid IPv6 address"))?;
+        (ip, after.strip_prefix(':'))
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let valid_host = !host.is_empty()
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'));
+    if !valid_host {
+        return Err(invalid("invalid host"));
+    }
+    let port = match port {
+        Some(port) => port.parse::<u16>().ok().filter(|port| *port > 0).ok_or_else(|| invalid("invalid port"))?,
+        None => 443,
+    };
+
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+/// Parses the HTTP version argument of `dns_lookup_doh`
+///
+/// # Returns
+/// * `Ok(Protocol)` - `Protocol::Https` for 'h2', `Protocol::H3` for 'h3'
+/// * `Err` - Unknown version, or 'h3' without the `doh3` Cargo feature
+fn parse_doh_version(version: &str) -> std::result::Result<Protocol, Box<dyn Error>> {
+    match version.trim().to_lowercase().as_str() {
+        "h2" => Ok(Protocol::Https),
+        #[cfg(feature = "doh3")]
+        "h3" => Ok(Protocol::H3),
+        #[cfg(not(feature = "doh3"))]
+        "h3" => Err("DoH over HTTP/3 requires the extension to be built with the 'doh3' feature".into()),
+        _ => Err(format!("Unknown DoH HTTP version '{}'. Supported versions: h2, h3", version).into()),
+    }
+}
+
+/// Reads an optional resolver spec column into per-row resolvers
+///
+/// Rows with a NULL spec, or all rows if the column is absent, map to `None`
+/// and use the session resolver.
+///
+/// # Returns
+/// * `Ok(Vec)` - One entry per row
+/// * `Err` - A spec is invalid
+fn read_spec_resolvers(
+    input: &DataChunkHandle,
+    column: usize,
+) -> std::result::Result<Vec<Option<SharedResolver>>, Box<dyn Error>> {
+    if input.num_columns() <= column {
+        return Ok(vec![None; input.len()]);
+    }
+    read_varchar_column(input, column)
+        .into_iter()
+        .map(|spec| spec.map(|spec| GLOBAL_DNS_STATE.spec_resolver(&spec)).transpose())
+        .collect()
+}
+
+/// Returns whether the second `dns_lookup` argument names a name server
+///
+/// Record types are alphanumeric names starting with a letter, so values starting with
+/// a digit or `[`, or containing `:`, are name server addresses such as `1.1.1.1`,
+/// `9.9.9.9:53` or `[2620:fe::fe]:53`.
+fn is_nameserver_arg(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_ascii_digit() || c == '[') || value.contains(':')
+}
+
+/// Builds the resolver spec for a `dns_lookup` name server list
+///
+/// The spec keeps the servers in the given order, so the first one is queried first. The
+/// resolver then fails over between them and prefers the ones answering fastest, so every
+/// ordering of a list shares one cached resolver under a key of the sorted addresses.
+///
+/// # Arguments
+/// * `servers` - IP addresses with an optional port, e.g. `["1.1.1.1", "9.9.9.9:53"]`
+///
+/// # Returns
+/// * `Ok((String, String))` - The spec for `DnsResolverState::spec_resolver_with_key`, e.g.
+///   `9.9.9.9:53,1.1.1.1:53`, and its cache key, e.g. `1.1.1.1:53,9.9.9.9:53`
+/// * `Err` - The list is empty or an entry is not a name server address
+fn nameserver_list_spec(servers: &[String]) -> std::result::Result<(String, String), Box<dyn Error>> {
+    let addrs: Vec<SocketAddr> = servers
+        .iter()
+        .map(|server| {
+            let server = server.trim();
+            SocketAddr::from_str(server)
+                .or_else(|_| IpAddr::from_str(server).map(|ip| SocketAddr::new(ip, 53)))
+                .map_err(|_| format!("Invalid name server '{}' in name server list", server))
+        })
+        .collect::<std::result::Result<_, _>>()?;
+    if addrs.is_empty() {
+        return Err("Name server list must not be empty".into());
+    }
+    let mut spec_addrs: Vec<SocketAddr> = Vec::with_capacity(addrs.len());
+    for addr in addrs {
+        if !spec_addrs.contains(&addr) {
+            spec_addrs.push(addr);
+        }
+    }
+    let mut key_addrs = spec_addrs.clone();
+    key_addrs.sort();
+    let join = |addrs: &[SocketAddr]| addrs.iter().map(SocketAddr::to_string).collect::<Vec<_>>().join(",");
+    Ok((join(&spec_addrs), format!("list:{}", join(&key_addrs))))
+}
+
+/// Parses the second `dns_lookup` argument as a transport protocol
+///
+/// # Returns
+/// * `Some(Protocol)` - The value is 'udp' or 'tcp' (case-insensitive)
+/// * `None` - Any other value, e.g. a record type
+fn parse_transport_arg(value: &str) -> Option<Protocol> {
+    match value.to_lowercase().as_str() {
+        "udp" => Some(Protocol::Udp),
+        "tcp" => Some(Protocol::Tcp),
+        _ => None,
+    }
+}
+
+/// Which address `dns_lookup` returns when a name has several
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum AddressStrategy {
+    /// The first address in resolver order (the default)
+    First,
+    /// An address picked uniformly at random, per row
+    Random,
+    /// All addresses, comma-separated
+    Join,
+}
+
+impl AddressStrategy {
+    /// Picks the result of a row from the addresses of its lookup
+    ///
+    /// # Returns
+    /// * `Some(String)` - The selected address, or all addresses joined with ','
+    /// * `None` - There are no addresses
+    fn pick(&self, addresses: &[String]) -> Option<String> {
+        if addresses.is_empty() {
+            return None;
+        }
+        match self {
+            AddressStrategy::First => Some(addresses[0].clone()),
+            AddressStrategy::Random => {
+                let index = RandomState::new().build_hasher().finish() as usize % addresses.len();
+                Some(addresses[index].clone())
+            }
+            AddressStrategy::Join => Some(addresses.join(",")),
+        }
+    }
+}
+
+/// Parses the second `dns_lookup` argument as an address selection strategy
+///
+/// # Returns
+/// * `Some(AddressStrategy)` - The value is 'first', 'random' or 'join' (case-insensitive)
+/// * `None` - Any other value, e.g. a record type
+fn parse_address_strategy(value: &str) -> Option<AddressStrategy> {
+    match value.to_lowercase().as_str() {
+        "first" => Some(AddressStrategy::First),
+        "random" => Some(AddressStrategy::Random),
+        "join" => Some(AddressStrategy::Join),
+        _ => None,
+    }
+}
+
+/// Address families returned by the address lookups of `dns_lookup` and `dns_lookup_all`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AddressFamily {
+    /// Only IPv4 addresses (the default)
+    Ipv4,
+    /// Only IPv6 addresses
+    Ipv6,
+    /// IPv4 addresses followed by IPv6 addresses
+    Any,
+}
+
+impl AddressFamily {
+    /// Returns the error message of a lookup without addresses of this family
+    fn not_found_message(&self) -> &'static str {
+        match self {
+            AddressFamily::Ipv4 => "No IPv4 addresses found for hostname",
+            AddressFamily::Ipv6 => "No IPv6 addresses found for hostname",
+            AddressFamily::Any => "No addresses found for hostname",
+        }
+    }
+
+    /// Returns the family as accepted by `set_dns_address_family`
+    fn as_str(&self) -> &'static str {
+        match self {
+            AddressFamily::Ipv4 => "ipv4",
+            AddressFamily::Ipv6 => "ipv6",
+            AddressFamily::Any => "any",
+        }
+    }
+}
+
+/// Parses an address family string into an AddressFamily enum
+///
+/// # Arguments
+/// * `family_str` - String containing the family (case-insensitive): 'ipv4', 'ipv6' or 'any'
+///
+/// # Returns
+/// * `Ok(AddressFamily)` - Successfully parsed family
+/// * `Err` - Unsupported family
+fn parse_address_family(family_str: &str) -> std::result::Result<AddressFamily, Box<dyn Error>> {
+    match family_str.trim().to_lowercase().as_str() {
+        "ipv4" => Ok(AddressFamily::Ipv4),
+        "ipv6" => Ok(AddressFamily::Ipv6),
+        "any" => Ok(AddressFamily::Any),
+        _ => Err(format!("Unknown address family '{}'. Supported families: ipv4, ipv6, any", family_str).into()),
+    }
+}
+
+/// Default ndots threshold, as in resolv.conf
+const DEFAULT_NDOTS: usize = 1;
+
+/// Order in which a relative name and its search-domain expansions are tried
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SearchOrder {
+    /// resolv.conf behavior: names with more than `n` labels are tried bare first,
+    /// shorter names are tried with the search domains first
+    Ndots(usize),
+    /// Always try the bare name before the search domains
+    BareFirst,
+    /// Always try the search domains before the bare name
+    SearchFirst,
+}
+
+impl SearchOrder {
+    /// Returns the ndots threshold that makes the resolver follow this order
+    ///
+    /// The resolver tries a name bare first when its label count exceeds ndots, so
+    /// 0 always tries bare names first and the maximum label count (127) never does.
+    fn ndots(&self) -> usize {
+        match self {
+            SearchOrder::Ndots(ndots) => *ndots,
+            SearchOrder::BareFirst => 0,
+            SearchOrder::SearchFirst => 127,
+        }
+    }
+
+    /// Returns the order as shown in `set_dns_search_order` messages
+    fn describe(&self) -> String {
+        match self {
+            SearchOrder::Ndots(ndots) => format!("'ndots' (ndots={})", ndots),
+            SearchOrder::BareFirst => "'bare_first'".to_string(),
+            SearchOrder::SearchFirst => "'search_first'".to_string(),
+        }
+    }
+}
+
+/// Parses a search order string into a SearchOrder enum
+///
+/// # Arguments
+/// * `order_str` - String containing the order (case-insensitive): 'ndots', 'bare_first' or 'search_first'
+/// * `ndots` - Threshold for 'ndots' (default: 1); only allowed with 'ndots'
+///
+/// # Returns
+/// * `Ok(SearchOrder)` - Successfully parsed order
+/// * `Err` - Unsupported order or invalid ndots
+fn parse_search_order(order_str: &str, ndots: Option<i64>) -> std::result::Result<SearchOrder, Box<dyn Error>> {
+    match (order_str.trim().to_lowercase().as_str(), ndots) {
+        ("ndots", None) => Ok(SearchOrder::Ndots(DEFAULT_NDOTS)),
+        ("ndots", Some(ndots @ 0..=15)) => Ok(SearchOrder::Ndots(ndots as usize)),
+        ("ndots", Some(ndots)) => Err(format!("ndots must be between 0 and 15, got {}", ndots).into()),
+        ("bare_first", None) => Ok(SearchOrder::BareFirst),
+        ("search_first", None) => Ok(SearchOrder::SearchFirst),
+        ("bare_first" | "search_first", Some(_)) => Err("ndots is only supported with order 'ndots'".into()),
+        _ => Err(format!(
+            "Unknown search order '{}'. Supported orders: ndots, bare_first, search_first",
+            order_str
+        )
+        .into()),
+    }
+}
+
+/// DNS resolver presets supported by `set_dns_config`
+const DNS_PRESETS: [&str; 5] = ["default", "system", "google", "cloudflare", "quad9"];
+
+/// Static host entries the session resolver consults before querying DNS
+enum HostsSource {
+    /// The system hosts file (`/etc/hosts`), read when the resolver is built
+    System,
+    /// No static entries, every name is resolved through DNS
+    Disabled,
+    /// Entries loaded from a hosts-format file
+    File { path: String, hosts: Arc<Hosts> },
+}
+
+impl HostsSource {
+    /// Returns the source as shown in `set_dns_hosts` messages
+    fn describe(&self) -> String {
+        match self {
+            HostsSource::System => "'system'".to_string(),
+            HostsSource::Disabled => "'none'".to_string(),
+            HostsSource::File { path, .. } => format!("file '{}'", path),
+        }
+    }
+}
+
+/// Parses a hosts source string into a HostsSource
+///
+/// # Arguments
+/// * `source_str` - 'system' (case-insensitive) for the system hosts file, 'none' to
+///   disable static entries, or the path of a hosts-format file to load
+///
+/// # Returns
+/// * `Ok(HostsSource)` - Successfully parsed source, with the file loaded
+/// * `Err` - Empty source, or the file cannot be read
+fn parse_hosts_source(source_str: &str) -> std::result::Result<HostsSource, Box<dyn Error>> {
+    let source = source_str.trim();
+    match source.to_lowercase().as_str() {
+        "" => Err("Hosts source must not be empty, expected 'system', 'none' or a file path".into()),
+        "system" => Ok(HostsSource::System),
+        "none" => Ok(HostsSource::Disabled),
+        _ => {
+            let file = std::fs::File::open(source)
+                .map_err(|e| format!("Failed to open hosts file '{}': {}", source, e))?;
+            let mut hosts = Hosts::default();
+            hosts
+                .read_hosts_conf(file)
+                .map_err(|e| format!("Failed to read hosts file '{}': {}", source, e))?;
+            Ok(HostsSource::File {
+                path: source.to_string(),
+                hosts: Arc::new(hosts),
+            })
+        }
+    }
+}
+
+/// Transport protocol used to reach the configured DNS servers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DnsProtocol {
+    /// Plain DNS over UDP (with TCP fallback for truncated answers)
+    Udp,
+    /// DNS-over-HTTPS over HTTP/3 (QUIC), requires the `doh3` Cargo feature
+    #[cfg(feature = "doh3")]
+    Doh3,
+}
+
+impl DnsProtocol {
+    /// Returns the name of the protocol as accepted by `set_dns_protocol`
+    fn name(&self) -> &'static str {
+        match self {
+            DnsProtocol::Udp => "udp",
+            #[cfg(feature = "doh3")]
+            DnsProtocol::Doh3 => "doh3",
+        }
+    }
+}
+
+/// Parses a protocol string into a DnsProtocol enum
+///
+/// # Arguments
+/// * `protocol_str` - String containing the protocol (case-insensitive): 'udp' or 'doh3'
+///
+/// # Returns
+/// * `Ok(DnsProtocol)` - Successfully parsed protocol
+/// * `Err` - Unsupported protocol, or a protocol not compiled into this build
+fn parse_dns_protocol(protocol_str: &str) -> std::result::Result<DnsProtocol, Box<dyn Error>> {
+    match protocol_str.trim().to_lowercase().as_str() {
+        "udp" => Ok(DnsProtocol::Udp),
+        #[cfg(feature = "doh3")]
+        "doh3" => Ok(DnsProtocol::Doh3),
+        #[cfg(not(feature = "doh3"))]
+        "doh3" => Err("Protocol 'doh3' requires the extension to be built with the 'doh3' feature".into()),
+        _ => Err(format!("Unknown protocol '{}'. Supported protocols: udp, doh3", protocol_str).into()),
+    }
+}
+
+/// Name server addresses and TLS name of the providers that serve DNS-over-HTTP/3
+#[cfg(feature = "doh3")]
+fn doh3_provider(preset: &str) -> Option<(Vec<IpAddr>, &'static str)> {
+    let (ips, tls_dns_name): (&[&str], &str) = match preset {
+        "google" => (&["8.8.8.8", "8.8.4.4", "2001:4860:4860::8888", "2001:4860:4860::8844"], "dns.google"),
+        "cloudflare" => (&["1.1.1.1", "1.0.0.1", "2606:4700:4700::1111", "2606:4700:4700::1001"], "cloudflare-dns.com"),
+        "quad9" => (&["9.9.9.9", "149.112.112.112", "2620:fe::fe", "2620:fe::9"], "dns.quad9.net"),
+        _ => return None,
+    };
+    Some((ips.iter().filter_map(|ip| IpAddr::from_str(ip).ok()).collect(), tls_dns_name))
+}
+
+/// Reads the name servers and search domains of the system resolver configuration
+///
+/// On Unix this is `/etc/resolv.conf`. Minimal containers often ship without one, or
+/// with one that lists no name servers, see `system_config_or_default`.
+fn read_system_config() -> std::result::Result<ResolverConfig, ResolveError> {
+    hickory_resolver::system_conf::read_system_conf().map(|(config, _)| config)
+}
+
+/// Picks the configuration of the 'system' preset from a system configuration read
+///
+/// Falls back to the 'default' servers rather than a resolver that never answers.
+///
+/// # Arguments
+/// * `read` - The result of reading the system configuration, see `read_system_config`
+///
+/// # Returns
+/// The configuration to use, and why the system configuration was unusable (missing,
+/// unreadable, or without name servers) if the default servers are used instead
+fn system_config_or_default<E: std::fmt::Display>(
+    read: std::result::Result<ResolverConfig, E>,
+) -> (ResolverConfig, Option<String>) {
+    match read {
+        Ok(config) if !config.name_servers().is_empty() => (config, None),
+        Ok(_) => (ResolverConfig::default(), Some("no name servers configured".to_string())),
+        Err(e) => (ResolverConfig::default(), Some(e.to_string())),
+    }
+}
+
+/// Builds the resolver configuration for a preset and protocol
+///
+/// # Arguments
+/// * `preset` - One of `DNS_PRESETS`
+/// * `protocol` - The transport protocol to reach the preset's servers with
+///
+/// # Returns
+/// * `Ok(ResolverConfig)` - The configuration; 'system' falls back to the 'default'
+///   servers if the system configuration is unusable, see `system_config_or_default`
+/// * `Err` - Unknown preset, or the preset does not support the protocol
+fn preset_config(preset: &str, protocol: DnsProtocol) -> std::result::Result<ResolverConfig, Box<dyn Error>> {
+    match protocol {
+        DnsProtocol::Udp => match preset {
+            "default" => Ok(ResolverConfig::default()),
+            "system" => Ok(system_config_or_default(read_system_config()).0),
+            "google" => Ok(ResolverConfig::google()),
+            "cloudflare" => Ok(ResolverConfig::cloudflare()),
+            "quad9" => Ok(ResolverConfig::quad9()),
+            _ => Err(format!("Unknown preset '{}'", preset).into()),
+        },
+        #[cfg(feature = "doh3")]
+        DnsProtocol::Doh3 => {
+            let (ips, tls_dns_name) = doh3_provider(preset).ok_or_else(|| {
+                format!("Protocol 'doh3' requires a provider preset (google, cloudflare, quad9), current preset is '{}'", preset)
+            })?;
+            Ok(ResolverConfig::from_parts(
+                None,
+                vec![],
+                NameServerConfigGroup::from_ips_h3(&ips, 443, tls_dns_name.to_string(), true),
+            ))
+        }
+    }
+}
+
+/// Validates and parses an IPv4 or IPv6 address string
+///
+/// A zone id suffix on an IPv6 address (e.g. "fe80::1%eth0") is ignored.
+///
+/// # Arguments
+/// * `ip_str` - A string slice containing an IPv4 or IPv6 address
+///
+/// # Returns
+/// * `Ok(IpAddr)` - Successfully parsed IP address
+/// * `Err` - Invalid IP address format
+fn validate_ip(ip_str: &str) -> std::result::Result<IpAddr, Box<dyn Error>> {
+    let trimmed = ip_str.trim();
+    let addr = match trimmed.split_once('%') {
+        Some((addr, _zone_id)) if addr.contains(':') => addr,
+        _ => trimmed,
+    };
+    match IpAddr::from_str(addr) {
+        Ok(addr) => Ok(addr),
+        Err(_) => Err(format!("Invalid IP address format: {}", ip_str).into()),
+    }
+}
+
+/// An IPv4 or IPv6 network in CIDR notation (e.g. "192.0.2.0/24", "2001:db8::/32")
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Returns true if the address belongs to this network
+    ///
+    /// Addresses of the other IP family never match.
+    fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.network, addr) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(*addr) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(*addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parses a CIDR string into a Cidr
+///
+/// # Arguments
+/// * `cidr_str` - A string slice containing a network in CIDR notation, or a bare
+///   address (treated as a single-host network)
+///
+/// # Returns
+/// * `Ok(Cidr)` - Successfully parsed network
+/// * `Err` - Invalid address or prefix length
+fn parse_cidr(cidr_str: &str) -> std::result::Result<Cidr, Box<dyn Error>> {
+    let invalid = || -> Box<dyn Error> { format!("Invalid CIDR notation: {}", cidr_str).into() };
+    let trimmed = cidr_str.trim();
+    let (addr_str, prefix_str) = match trimmed.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (trimmed, None),
+    };
+
+    let network = IpAddr::from_str(addr_str).map_err(|_| invalid())?;
+    let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+    let prefix_len = match prefix_str {
+        Some(prefix) => prefix.parse::<u8>().map_err(|_| invalid())?,
+        None => max_prefix,
+    };
+    if prefix_len > max_prefix {
+        return Err(invalid());
+    }
+
+    Ok(Cidr { network, prefix_len })
+}
+
+/// Default smallest IPv4 prefix length `reverse_dns_range` enumerates (65,536 addresses)
+const REVERSE_RANGE_MIN_PREFIX: i64 = 16;
+
+/// Host bits of the largest network `reverse_dns_range` enumerates whatever `min_prefix`
+/// allows: an IPv4 /12 or IPv6 /108, 1,048,576 addresses
+const REVERSE_RANGE_MAX_HOST_BITS: u32 = 20;
+
+/// Enumerates the host addresses of a network for `reverse_dns_range`
+///
+/// IPv4 networks larger than /31 skip their network and broadcast addresses. The range
+/// guard is expressed as an IPv4 prefix length; IPv6 networks may hold as many
+/// addresses as an IPv4 network of that prefix (e.g. /16 allows an IPv6 /112). No
+/// network may exceed `REVERSE_RANGE_MAX_HOST_BITS`, even with a lower `min_prefix`.
+///
+/// # Arguments
+/// * `cidr` - The network to enumerate; host bits of its address are ignored
+/// * `min_prefix` - The smallest IPv4 prefix length accepted (0-32)
+///
+/// # Returns
+/// * `Ok(Vec<IpAddr>)` - The host addresses in ascending order
+/// * `Err` - The network is larger than the guard or the hard limit allows
+fn cidr_host_addresses(cidr: &Cidr, min_prefix: u8) -> std::result::Result<Vec<IpAddr>, Box<dyn Error>> {
+    let max_host_bits = 32 - u32::from(min_prefix);
+    let host_bits = match cidr.network {
+        IpAddr::V4(_) => 32 - u32::from(cidr.prefix_len),
+        IpAddr::V6(_) => 128 - u32::from(cidr.prefix_len),
+    };
+    if host_bits > REVERSE_RANGE_MAX_HOST_BITS {
+        return Err(format!(
+            "CIDR range /{} holds more than the maximum of {} addresses",
+            cidr.prefix_len,
+            1u64 << REVERSE_RANGE_MAX_HOST_BITS
+        )
+        .into());
+    }
+    match cidr.network {
+        IpAddr::V4(network) => {
+            if host_bits > max_host_bits {
+                return Err(format!(
+                    "CIDR range /{} is larger than the limit of /{}, raise it with min_prefix",
+                    cidr.prefix_len, min_prefix
+                )
+                .into());
+            }
+            let first = u32::from(network) & u32::MAX.checked_shl(host_bits).unwrap_or(0);
+            let last = first | (u64::from(u32::MAX) >> (32 - host_bits)) as u32;
+            let (first, last) = if host_bits >= 2 { (first + 1, last - 1) } else { (first, last) };
+            Ok((first..=last).map(|addr| IpAddr::V4(Ipv4Addr::from(addr))).collect())
+        }
+        IpAddr::V6(network) => {
+            if host_bits > max_host_bits {
+                return Err(format!(
+                    "CIDR range /{} holds more addresses than the limit of an IPv4 /{}, raise it with min_prefix",
+                    cidr.prefix_len, min_prefix
+                )
+                .into());
+            }
+            let first = u128::from(network) & u128::MAX.checked_shl(host_bits).unwrap_or(0);
+            let count = 1u128 << host_bits;
+            Ok((0..count).map(|offset| IpAddr::V6(Ipv6Addr::from(first + offset))).collect())
+        }
+    }
+}
+
+/// Returns true if the address falls into a network of the `set_dns_ip_filter` filter
+fn is_filtered_addr(addr: &IpAddr) -> bool {
+    GLOBAL_DNS_STATE.ip_filter.load().iter().any(|cidr| cidr.contains(addr))
+}
+
+/// Returns true if the record is an A or AAAA record whose address is filtered
+fn is_filtered_record(rdata: &RData) -> bool {
+    match rdata {
+        RData::A(a) => is_filtered_addr(&IpAddr::V4(a.0)),
+        RData::AAAA(aaaa) => is_filtered_addr(&IpAddr::V6(aaaa.0)),
+        _ => false,
+    }
+}
+
+/// Performs an asynchronous reverse DNS lookup for an IPv4 or IPv6 address
+///
+/// IPv4 addresses are queried under in-addr.arpa, IPv6 addresses under ip6.arpa.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `ip_str` - String containing the IPv4 or IPv6 address to resolve
+///
+/// # Returns
+/// * `Ok(String)` - The resolved hostname
+/// * `Err` - Lookup failed or invalid IP address
+async fn reverse_dns_lookup_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    ip_str: &str,
+) -> std::result::Result<String, Box<dyn Error>> {
+    let ip_addr = validate_ip(ip_str)?;
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, &ip_addr.to_string(), RecordType::PTR, resolver_guard.reverse_lookup(ip_addr)).await {
+        Ok(lookup) => {
+            // Get the first hostname from the lookup result
+            if let Some(name) = lookup.iter().next() {
+                Ok(answer_name(name))
+            } else {
+                Err("No hostname found for IP address".into())
+            }
+        }
+        Err(e) => Err(format!("Reverse DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs an asynchronous reverse DNS lookup, returning all PTR hostnames
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `ip_str` - String containing the IPv4 or IPv6 address to resolve
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - All resolved hostnames in the resolver's order
+/// * `Err` - No PTR records found, lookup failed or invalid IP address
+async fn reverse_dns_lookup_all_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    ip_str: &str,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let ip_addr = validate_ip(ip_str)?;
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, &ip_addr.to_string(), RecordType::PTR, resolver_guard.reverse_lookup(ip_addr)).await {
+        Ok(lookup) => {
+            let names: Vec<String> = lookup
+                .iter()
+                .map(|name| answer_name(name))
+                .collect();
+            if names.is_empty() {
+                return Err("No hostname found for IP address".into());
+            }
+            Ok(names)
+        }
+        Err(e) => Err(format!("Reverse DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Converts a hostname to its ASCII-compatible encoding for the resolver
+///
+/// Unicode hostnames (e.g. "münchen.de") are converted to punycode via IDNA
+/// ("xn--mnchen-3ya.de"); ASCII hostnames are only trimmed. Both are lowercased, as
+/// DNS names are case-insensitive.
+///
+/// # Returns
+/// * `Ok(String)` - The lowercase ASCII hostname
+/// * `Err` - A label is not valid under IDNA
+fn to_ascii_hostname(hostname: &str) -> std::result::Result<String, Box<dyn Error>> {
+    let hostname = hostname.trim();
+    if hostname.is_ascii() {
+        return Ok(hostname.to_ascii_lowercase());
+    }
+    Name::from_utf8(hostname)
+        .map(|name| name.to_ascii().to_ascii_lowercase())
+        .map_err(|e| format!("Invalid internationalized hostname '{}': {}", hostname, e).into())
+}
+
+/// Formats a domain name from a DNS answer for output: lowercase, without trailing dot
+///
+/// Servers may return names in any case, so lowercasing keeps results stable for joins.
+fn answer_name(name: &Name) -> String {
+    name_to_text(&name.to_lowercase())
+}
+
+/// Renders a domain name as UTF-8 text, without trailing dot
+///
+/// Hickory's own formatting escapes every non-ASCII byte as an octal `\DDD` sequence,
+/// mangling names that servers return in raw UTF-8. Labels are decoded as UTF-8 instead
+/// and `xn--` labels converted from punycode. A `.` or `\` inside a label stays escaped
+/// so the name remains unambiguous; control characters and bytes that are not valid
+/// UTF-8 are written as RFC 1035 decimal `\DDD` escapes.
+fn name_to_text(name: &Name) -> String {
+    let mut labels = Vec::with_capacity(name.num_labels() as usize);
+    for label in name.iter() {
+        if label.starts_with(b"xn--") {
+            if let Ok(label) = Label::from_raw_bytes(label) {
+                labels.push(label.to_utf8());
+                continue;
+            }
+        }
+        let mut text = String::with_capacity(label.len());
+        for chunk in label.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                match c {
+                    '.' | '\\' => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    c if c.is_control() => {
+                        for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                            text.push_str(&format!("\\{:03}", byte));
+                        }
+                    }
+                    c => text.push(c),
+                }
+            }
+            for byte in chunk.invalid() {
+                text.push_str(&format!("\\{:03}", byte));
+            }
+        }
+        labels.push(text);
+    }
+    labels.join(".")
+}
+
+/// Looks up the addresses of a hostname in one address family, in resolver order
+///
+/// The address lookup stops at the A records of dual-stack names, so IPv6 addresses
+/// are queried as AAAA records directly.
+///
+/// # Arguments
+/// * `resolver` - The resolver performing the lookups
+/// * `hostname` - The ASCII hostname to resolve
+/// * `family` - The address family to return
+///
+/// # Returns
+/// * `Ok(Vec<IpAddr>)` - The addresses of the family, IPv4 before IPv6 for `Any`
+/// * `Err` - The lookup failed (for `Any`, both lookups failed)
+async fn lookup_family_addrs(
+    resolver: &Resolver<TokioConnectionProvider>,
+    hostname: &str,
+    family: AddressFamily,
+) -> std::result::Result<Vec<IpAddr>, ResolveError> {
+    let ipv4 = async {
+        let lookup = tracked(resolver, hostname, RecordType::A, resolver.lookup_ip(hostname)).await?;
+        Ok::<_, ResolveError>(lookup.iter().filter(IpAddr::is_ipv4).collect::<Vec<_>>())
+    };
+    let ipv6 = async {
+        let lookup = tracked(resolver, hostname, RecordType::AAAA, resolver.ipv6_lookup(hostname)).await?;
+        Ok::<_, ResolveError>(lookup.iter().map(|aaaa| IpAddr::V6(aaaa.0)).collect::<Vec<_>>())
+    };
+    match family {
+        AddressFamily::Ipv4 => ipv4.await,
+        AddressFamily::Ipv6 => ipv6.await,
+        AddressFamily::Any => match futures::join!(ipv4, ipv6) {
+            (Err(e), Err(_)) => Err(e),
+            (ipv4, ipv6) => Ok(ipv4.unwrap_or_default().into_iter().chain(ipv6.unwrap_or_default()).collect()),
+        },
+    }
+}
+
+/// Performs an asynchronous forward DNS lookup, returning the first address
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+/// * `family` - The address family to return, see `set_dns_address_family`
+///
+/// # Returns
+/// * `Ok(String)` - The first address of the family found
+/// * `Err` - No addresses of the family found or lookup failed
+async fn dns_lookup_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    family: AddressFamily,
+) -> std::result::Result<String, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match lookup_family_addrs(&resolver_guard, hostname, family).await {
+        // Find the first address outside the IP filter
+        Ok(ips) => match ips.iter().find(|ip| !is_filtered_addr(ip)) {
+            Some(ip) => Ok(format_ip_addr(ip)),
+            None => Err(family.not_found_message().into()),
+        },
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs an asynchronous forward DNS lookup, returning all addresses
+///
+/// # Arguments
+/// * `resolver` - Reference to the RwLock-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+/// * `family` - The address family to return, see `set_dns_address_family`
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - All addresses of the family found
+/// * `Err` - No addresses of the family found or lookup failed
+async fn dns_lookup_all_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    family: AddressFamily,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match lookup_family_addrs(&resolver_guard, hostname, family).await {
+        Ok(ips) => {
+            if ips.is_empty() {
+                Err(family.not_found_message().into())
+            } else {
+                // Addresses inside the IP filter are dropped, possibly leaving an empty list
+                Ok(ips.iter().filter(|ip| !is_filtered_addr(ip)).map(format_ip_addr).collect())
+            }
+        }
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs an asynchronous forward DNS lookup, returning the name that answered
+///
+/// The resolver may try several candidate names for a short hostname (search domains,
+/// `ndots`); the query of the returned lookup is the candidate that produced the answer.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(String)` - The matched fully qualified name, without trailing dot
+/// * `Err` - The name does not resolve or the lookup failed
+async fn dns_lookup_resolved_fqdn_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<String, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.lookup_ip(hostname)).await {
+        Ok(lookup) => Ok(name_to_text(lookup.query().name())),
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Formats an IP address for output, scoping link-local IPv6 addresses
+///
+/// DNS answers never carry a zone id, so link-local (fe80::/10) addresses are only
+/// usable once scoped to an interface. If a zone id is configured via
+/// `set_dns_ipv6_zone_id`, it is appended as `fe80::1%eth0`; otherwise the
+/// address is returned unscoped.
+fn format_ip_addr(addr: &IpAddr) -> String {
+    match addr {
+        IpAddr::V6(ipv6) if ipv6.segments()[0] & 0xffc0 == 0xfe80 => {
+            match GLOBAL_DNS_STATE.ipv6_zone_id.load().as_deref() {
+                Some(zone_id) => format!("{}%{}", ipv6, zone_id),
+                None => ipv6.to_string(),
+            }
+        }
+        _ => addr.to_string(),
+    }
+}
+
+/// Formats the data of a DNS record for output
+///
+/// Addresses go through `format_ip_addr`, all other record types use their
+/// presentation format.
+fn format_record_data(rdata: &RData) -> String {
+    match rdata {
+        RData::AAAA(aaaa) => format_ip_addr(&IpAddr::V6(aaaa.0)),
+        _ => rdata.to_string(),
+    }
+}
+
+/// Performs an asynchronous AAAA lookup, returning all IPv6 addresses
+///
+/// Queries AAAA records directly, so dual-stack hosts return their IPv6 addresses
+/// (an address lookup would stop at the A records).
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - All IPv6 addresses in compressed notation, without those in the IP filter
+/// * `Err` - No IPv6 addresses found or lookup failed
+async fn dns_lookup_aaaa_all_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let hostname = hostname.trim();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::AAAA, resolver_guard.ipv6_lookup(hostname)).await {
+        Ok(lookup) => {
+            let ips: Vec<IpAddr> = lookup.iter().map(|aaaa| IpAddr::V6(aaaa.0)).collect();
+
+            if ips.is_empty() {
+                Err("No IPv6 addresses found for hostname".into())
+            } else {
+                // Addresses inside the IP filter are dropped, possibly leaving an empty list
+                Ok(ips
+                    .iter()
+                    .filter(|ip| !is_filtered_addr(ip))
+                    .map(format_ip_addr)
+                    .collect())
+            }
+        }
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Maximum number of CNAME hops followed by `dns_lookup_cname_async`
+const MAX_CNAME_HOPS: usize = 16;
+
+/// Performs asynchronous CNAME lookups, following the chain to its canonical target
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(Some(String))` - The final target of the CNAME chain, without the trailing dot
+/// * `Ok(None)` - The name has no CNAME record
+/// * `Err` - Lookup failed, or the chain loops or exceeds `MAX_CNAME_HOPS`
+async fn dns_lookup_cname_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Option<String>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let mut chain = vec![normalize_name(&hostname)];
+    let mut target: Option<String> = None;
+    for _ in 0..MAX_CNAME_HOPS {
+        let current = target.clone().unwrap_or_else(|| hostname.clone());
+        let owner = normalize_name(&current);
+        let next = match tracked(&resolver_guard, &current, RecordType::CNAME, resolver_guard.lookup(current.as_str(), RecordType::CNAME)).await {
+            // Only a CNAME owned by the current name continues the chain
+            Ok(lookup) => lookup.record_iter().find_map(|record| match record.data() {
+                RData::CNAME(cname) if normalize_name(&record.name().to_string()) == owner => {
+                    Some(answer_name(&cname.0))
+                }
+                _ => None,
+            }),
+            Err(e) if e.is_no_records_found() => None,
+            Err(e) => return Err(format!("DNS lookup failed: {}", e).into()),
+        };
+
+        match next {
+            Some(next) if chain.contains(&normalize_name(&next)) => {
+                return Err(format!("CNAME loop detected at {}", next).into());
+            }
+            Some(next) => {
+                chain.push(normalize_name(&next));
+                target = Some(next);
+            }
+            None => return Ok(target),
+        }
+    }
+    Err(format!("CNAME chain longer than {} hops", MAX_CNAME_HOPS).into())
+}
+
+/// Performs asynchronous MX lookups
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the domain to resolve
+///
+/// # Returns
+/// * `Ok(Vec<(u16, String)>)` - Preference and exchange of every MX record, sorted by
+///   preference ascending; records with equal preference keep the resolver's order
+/// * `Err` - No MX records exist or the lookup failed
+async fn dns_lookup_mx_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<(u16, String)>, Box<dyn Error>> {
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, hostname, RecordType::MX, resolver_guard.mx_lookup(hostname))
+        .await
+        .map_err(|e| format!("MX lookup failed: {}", e))?;
+    let mut records: Vec<(u16, String)> = lookup
+        .iter()
+        .map(|mx| (mx.preference(), answer_name(mx.exchange())))
+        .collect();
+    if records.is_empty() {
+        return Err("No MX records found".into());
+    }
+    // Stable sort so equal-preference records are all kept in order
+    records.sort_by_key(|(preference, _)| *preference);
+    Ok(records)
+}
+
+/// Performs asynchronous TXT lookups, returning one string per record
+///
+/// A TXT record may be split into several character-strings on the wire; they are
+/// concatenated without separator so long SPF/DKIM records come back whole.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - One entry per TXT record, non-UTF-8 bytes decoded lossily
+/// * `Err` - No TXT records exist or the lookup failed
+async fn dns_lookup_txt_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, hostname, RecordType::TXT, resolver_guard.txt_lookup(hostname))
+        .await
+        .map_err(|e| format!("TXT lookup failed: {}", e))?;
+    let records: Vec<String> = lookup.iter().map(txt_to_string).collect();
+    if records.is_empty() {
+        return Err("No TXT records found".into());
+    }
+    Ok(records)
+}
+
+/// Concatenates the character-strings of a TXT record, decoding non-UTF-8 bytes lossily
+fn txt_to_string(txt: &TXT) -> String {
+    let bytes: Vec<u8> = txt.txt_data().iter().flat_map(|chunk| chunk.iter().copied()).collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Smallest and largest EDNS UDP payload sizes accepted by `dns_lookup_txt`
+const EDNS_BUF_SIZE_RANGE: std::ops::RangeInclusive<i32> = 512..=65535;
+
+/// Performs an asynchronous TXT lookup advertising an EDNS UDP payload size
+///
+/// Queries the first plain name server of the session configuration directly, so
+/// large records (e.g. long DKIM keys) fit into a single UDP response instead of
+/// being truncated. Truncated responses are still retried over TCP.
+///
+/// # Arguments
+/// * `hostname` - String containing the hostname to resolve
+/// * `edns_buf_size` - The UDP payload size to advertise, in bytes
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - One entry per TXT record, non-UTF-8 bytes decoded lossily
+/// * `Err` - No TXT records exist or the lookup failed
+async fn dns_lookup_txt_edns_async(
+    hostname: &str,
+    edns_buf_size: u16,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let (server, timeout) = GLOBAL_DNS_STATE.plain_name_server()?;
+
+    let response = raw_query(server, &hostname, RecordType::TXT, timeout, Some(edns_buf_size)).await?;
+    let records: Vec<String> = response
+        .answers()
+        .iter()
+        .filter_map(|record| match record.data() {
+            RData::TXT(txt) => Some(txt_to_string(txt)),
+            _ => None,
+        })
+        .collect();
+    if records.is_empty() {
+        return Err("No TXT records found".into());
+    }
+    Ok(records)
+}
+
+/// Returns true if a TXT record is an SPF policy (`v=spf1`, RFC 7208)
+fn is_spf_record(record: &str) -> bool {
+    let version = record.split_ascii_whitespace().next().unwrap_or("");
+    version.eq_ignore_ascii_case("v=spf1")
+}
+
+/// Extracts the domains of the `include:` mechanisms of an SPF policy
+///
+/// Qualifiers (`+`, `-`, `~`, `?`) are ignored and the domains are returned in
+/// policy order.
+fn spf_includes(record: &str) -> Vec<String> {
+    record
+        .split_ascii_whitespace()
+        .skip(1)
+        .filter_map(|term| {
+            let term = term.trim_start_matches(['+', '-', '~', '?']);
+            let (mechanism, domain) = term.split_once(':')?;
+            (mechanism.eq_ignore_ascii_case("include") && !domain.is_empty()).then(|| domain.to_string())
+        })
+        .collect()
+}
+
+/// Returns true if a TXT record is a DMARC policy (`v=DMARC1`, RFC 7489)
+fn is_dmarc_record(record: &str) -> bool {
+    let version = record.split(';').next().unwrap_or("").trim();
+    version.eq_ignore_ascii_case("v=DMARC1")
+}
+
+/// Extracts the value of the `p=` tag of a DMARC policy, lowercased
+///
+/// # Returns
+/// * `Some(String)` - The requested policy, e.g. "none", "quarantine" or "reject"
+/// * `None` - The record has no `p=` tag
+fn dmarc_policy(record: &str) -> Option<String> {
+    record.split(';').skip(1).find_map(|tag| {
+        let (name, value) = tag.split_once('=')?;
+        (name.trim().eq_ignore_ascii_case("p") && !value.trim().is_empty()).then(|| value.trim().to_lowercase())
+    })
+}
+
+/// Performs an asynchronous DMARC policy lookup
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `domain` - String containing the domain whose `_dmarc` name is resolved
+///
+/// # Returns
+/// * `Ok(String)` - The first TXT record at `_dmarc.<domain>` starting with `v=DMARC1`,
+///   with its chunks concatenated
+/// * `Err` - The domain publishes no DMARC record or the lookup failed
+async fn dns_lookup_dmarc_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    domain: &str,
+) -> std::result::Result<String, Box<dyn Error>> {
+    let name = format!("_dmarc.{}", domain.trim().trim_end_matches('.'));
+    dns_lookup_txt_async(resolver, &name)
+        .await?
+        .into_iter()
+        .find(|record| is_dmarc_record(record))
+        .ok_or_else(|| "No DMARC record found".into())
+}
+
+/// Performs an asynchronous SPF policy lookup
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `domain` - String containing the domain to resolve
+///
+/// # Returns
+/// * `Ok(String)` - The first TXT record starting with `v=spf1`, with its chunks concatenated
+/// * `Err` - The domain publishes no SPF record or the lookup failed
+async fn dns_lookup_spf_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    domain: &str,
+) -> std::result::Result<String, Box<dyn Error>> {
+    dns_lookup_txt_async(resolver, domain)
+        .await?
+        .into_iter()
+        .find(|record| is_spf_record(record))
+        .ok_or_else(|| "No SPF record found".into())
+}
+
+/// A decoded SRV record as returned by `dns_lookup_srv`
+struct SrvRecord {
+    priority: u16,
+    weight: u16,
+    port: u16,
+    target: String,
+}
+
+/// Performs asynchronous SRV lookups
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `name` - String containing the service name to resolve (e.g. "_sip._tcp.example.com")
+///
+/// # Returns
+/// * `Ok(Vec<SrvRecord>)` - All SRV records sorted by priority ascending; records with
+///   equal priority keep the resolver's order
+/// * `Err` - No SRV records exist or the lookup failed
+async fn dns_lookup_srv_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    name: &str,
+) -> std::result::Result<Vec<SrvRecord>, Box<dyn Error>> {
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, name, RecordType::SRV, resolver_guard.srv_lookup(name))
+        .await
+        .map_err(|e| format!("SRV lookup failed: {}", e))?;
+    let mut records: Vec<SrvRecord> = lookup
+        .iter()
+        .map(|srv| SrvRecord {
+            priority: srv.priority(),
+            weight: srv.weight(),
+            port: srv.port(),
+            target: answer_name(srv.target()),
+        })
+        .collect();
+    if records.is_empty() {
+        return Err("No SRV records found".into());
+    }
+    records.sort_by_key(|record| record.priority);
+    Ok(records)
+}
+
+/// Performs asynchronous NS lookups
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `domain` - String containing the domain to resolve
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - All name server hostnames without trailing dots, in resolver order
+/// * `Err` - No NS records exist at the queried name or the lookup failed
+async fn dns_lookup_ns_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    domain: &str,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let domain = domain.trim();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, domain, RecordType::NS, resolver_guard.ns_lookup(domain))
+        .await
+        .map_err(|e| format!("NS lookup failed: {}", e))?;
+    let name_servers: Vec<String> = lookup
+        .iter()
+        .map(|ns| answer_name(&ns.0))
+        .collect();
+    if name_servers.is_empty() {
+        return Err("No NS records found".into());
+    }
+    Ok(name_servers)
+}
+
+/// A decoded SOA record as returned by `dns_lookup_soa`
+struct SoaRecord {
+    mname: String,
+    rname: String,
+    serial: u32,
+    refresh: i32,
+    retry: i32,
+    expire: i32,
+    minimum: u32,
+}
+
+/// Performs an asynchronous SOA lookup
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `domain` - String containing the zone apex to resolve
+///
+/// # Returns
+/// * `Ok(SoaRecord)` - The SOA record of the name, with trailing dots stripped from
+///   `mname` and `rname`
+/// * `Err` - No SOA record exists at the name or the lookup failed
+async fn dns_lookup_soa_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    domain: &str,
+) -> std::result::Result<SoaRecord, Box<dyn Error>> {
+    let domain = domain.trim();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, domain, RecordType::SOA, resolver_guard.soa_lookup(domain))
+        .await
+        .map_err(|e| format!("SOA lookup failed: {}", e))?;
+    // A CNAME at the name leads to the target's SOA, which is not owned by the name
+    let owner = normalize_name(domain);
+    let soa = lookup
+        .as_lookup()
+        .record_iter()
+        .filter(|record| normalize_name(&record.name().to_string()) == owner)
+        .find_map(|record| match record.data() {
+            RData::SOA(soa) => Some(soa),
+            _ => None,
+        })
+        .ok_or("No SOA record found")?;
+    Ok(SoaRecord {
+        mname: name_to_text(soa.mname()),
+        rname: name_to_text(soa.rname()),
+        serial: soa.serial(),
+        refresh: soa.refresh(),
+        retry: soa.retry(),
+        expire: soa.expire(),
+        minimum: soa.minimum(),
+    })
+}
+
+/// Converts the `rname` of an SOA record into the email address it encodes
+///
+/// The first label is the local part and the rest the mail domain, so
+/// `hostmaster.example.com` becomes `hostmaster@example.com`. Dots inside the local
+/// part are escaped in the name (`john\.doe.example.com` is `john.doe@example.com`),
+/// also as RFC 1035 decimal escapes (`john\046doe.example.com`).
+///
+/// # Arguments
+/// * `rname` - The responsible-party name in presentation format, without trailing dot
+///
+/// # Returns
+/// * `Some(String)` - The email address
+/// * `None` - The name has a single label, an empty local part or a malformed escape
+fn soa_rname_email(rname: &str) -> Option<String> {
+    let mut local_part = Vec::new();
+    let mut chars = rname.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                let (j, escaped) = chars.next()?;
+                match rname.get(j..j + 3).filter(|digits| digits.bytes().all(|b| b.is_ascii_digit())) {
+                    Some(digits) => {
+                        local_part.push(digits.parse::<u8>().ok()?);
+                        chars.nth(1)?;
+                    }
+                    None => local_part.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes()),
+                }
+            }
+            '.' => {
+                let domain = &rname[i + 1..];
+                return (!local_part.is_empty() && !domain.is_empty())
+                    .then(|| format!("{}@{}", String::from_utf8_lossy(&local_part), domain));
+            }
+            _ => local_part.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    None
+}
+
+/// Performs asynchronous CAA lookups
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `domain` - String containing the domain to resolve
+///
+/// # Returns
+/// * `Ok(Vec<(u8, String, String)>)` - Flags, tag and value of every CAA record;
+///   values are the raw property values, lossily decoded
+/// * `Err` - No CAA records exist or the lookup failed
+async fn dns_lookup_caa_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    domain: &str,
+) -> std::result::Result<Vec<(u8, String, String)>, Box<dyn Error>> {
+    let domain = domain.trim();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, domain, RecordType::CAA, resolver_guard.lookup(domain, RecordType::CAA))
+        .await
+        .map_err(|e| format!("CAA lookup failed: {}", e))?;
+    let records: Vec<(u8, String, String)> = lookup
+        .iter()
+        .filter_map(|rdata| match rdata {
+            RData::CAA(caa) => Some((
+                caa.flags(),
+                caa.tag().as_str().to_string(),
+                String::from_utf8_lossy(caa.raw_value()).into_owned(),
+            )),
+            _ => None,
+        })
+        .collect();
+    if records.is_empty() {
+        return Err("No CAA records found".into());
+    }
+    Ok(records)
+}
+
+/// A TLSA record as returned by `dns_lookup_tlsa`
+struct TlsaRecord {
+    usage: u8,
+    selector: u8,
+    matching_type: u8,
+    certificate_data: String,
+}
+
+/// Performs asynchronous TLSA lookups (DANE, RFC 6698)
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `name` - String containing the TLSA owner name (e.g., "_443._tcp.example.com")
+///
+/// # Returns
+/// * `Ok(Vec<TlsaRecord>)` - Every TLSA record, the certificate association data as
+///   lowercase hex
+/// * `Err` - No TLSA records exist or the lookup failed
+async fn dns_lookup_tlsa_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    name: &str,
+) -> std::result::Result<Vec<TlsaRecord>, Box<dyn Error>> {
+    let name = to_ascii_hostname(name)?;
+    let name = name.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, name, RecordType::TLSA, resolver_guard.lookup(name, RecordType::TLSA))
+        .await
+        .map_err(|e| format!("TLSA lookup failed: {}", e))?;
+    let records: Vec<TlsaRecord> = lookup
+        .iter()
+        .filter_map(|rdata| match rdata {
+            RData::TLSA(tlsa) => Some(TlsaRecord {
+                usage: tlsa.cert_usage().into(),
+                selector: tlsa.selector().into(),
+                matching_type: tlsa.matching().into(),
+                certificate_data: tlsa.cert_data().iter().map(|byte| format!("{:02x}", byte)).collect(),
+            }),
+            _ => None,
+        })
+        .collect();
+    if records.is_empty() {
+        return Err("No TLSA records found".into());
+    }
+    Ok(records)
+}
+
+/// Checks an IPv4 address against a DNS-based blocklist (DNSBL, RFC 5782)
+///
+/// Queries the A record of the reversed octets under the blocklist zone, e.g.
+/// `2.0.0.127.zen.spamhaus.org` for `127.0.0.2`.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `ip_str` - String containing the IPv4 address to check
+/// * `zone` - String containing the blocklist zone (e.g., "zen.spamhaus.org")
+///
+/// # Returns
+/// * `Ok(true)` - The blocklist returned an A record, the address is listed
+/// * `Ok(false)` - NXDOMAIN or no A record, the address is not listed
+/// * `Err` - Invalid or non-IPv4 address, empty zone, or the lookup failed
+async fn dns_check_blocklist_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    ip_str: &str,
+    zone: &str,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let ip = match validate_ip(ip_str)? {
+        IpAddr::V4(ip) => ip,
+        IpAddr::V6(_) => return Err(format!("Blocklist checks require an IPv4 address: {}", ip_str).into()),
+    };
+    let zone = zone.trim().trim_end_matches('.');
+    if zone.is_empty() {
+        return Err("Blocklist zone must not be empty".into());
+    }
+    // Fully qualified, so search domains are never appended to the query
+    let query = format!("{}.{}.", reversed_labels(&IpAddr::V4(ip)), zone);
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, &query, RecordType::A, resolver_guard.ipv4_lookup(query.as_str())).await {
+        Ok(lookup) => Ok(lookup.iter().next().is_some()),
+        Err(e) if is_no_records_error(&e) => Ok(false),
+        Err(e) => Err(format!("Blocklist lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs a forward-confirmed reverse DNS (FCrDNS) check of an IPv4 or IPv6 address
+///
+/// Resolves the PTR hostnames of the address, then the A (or AAAA, for an IPv6 address)
+/// records of each hostname, and looks for the original address among them.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `ip_str` - String containing the IPv4 or IPv6 address to check
+///
+/// # Returns
+/// * `Ok(true)` - A PTR hostname resolves back to the address
+/// * `Ok(false)` - No PTR records, or no PTR hostname resolves back to the address
+/// * `Err` - Invalid IP address, or a lookup failed before the address was confirmed
+async fn dns_fcrdns_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    ip_str: &str,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let ip_addr = validate_ip(ip_str)?;
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let names: Vec<String> = match tracked(
+        &resolver_guard,
+        &ip_addr.to_string(),
+        RecordType::PTR,
+        resolver_guard.reverse_lookup(ip_addr),
+    )
+    .await
+    {
+        // Fully qualified, so search domains are never appended to the forward queries
+        Ok(lookup) => lookup.iter().map(|name| format!("{}.", answer_name(name))).collect(),
+        Err(e) if is_no_records_error(&e) => return Ok(false),
+        Err(e) => return Err(format!("Reverse DNS lookup failed: {}", e).into()),
+    };
+
+    let mut failure = None;
+    for name in &names {
+        let addresses: std::result::Result<Vec<IpAddr>, ResolveError> = match ip_addr {
+            IpAddr::V4(_) => tracked(&resolver_guard, name, RecordType::A, resolver_guard.ipv4_lookup(name.as_str()))
+                .await
+                .map(|lookup| lookup.iter().map(|a| IpAddr::V4(a.0)).collect()),
+            IpAddr::V6(_) => tracked(&resolver_guard, name, RecordType::AAAA, resolver_guard.ipv6_lookup(name.as_str()))
+                .await
+                .map(|lookup| lookup.iter().map(|aaaa| IpAddr::V6(aaaa.0)).collect()),
+        };
+        match addresses {
+            Ok(addresses) if addresses.contains(&ip_addr) => return Ok(true),
+            Ok(_) => {}
+            Err(e) if is_no_records_error(&e) => {}
+            Err(e) => failure = Some(e),
+        }
+    }
+    match failure {
+        Some(e) => Err(format!("DNS lookup failed: {}", e).into()),
+        None => Ok(false),
+    }
+}
+
+/// Returns the labels of an address in reverse order, as used by reverse DNS zones
+///
+/// IPv4 addresses are reversed by octet (`8.8.4.4` -> `4.4.8.8`), IPv6 addresses by
+/// nibble (`2001:db8::1` -> `1.0.0.0...8.b.d.0.1.0.0.2`).
+fn reversed_labels(ip: &IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            format!("{}.{}.{}.{}", d, c, b, a)
+        }
+        IpAddr::V6(ip) => ip
+            .octets()
+            .iter()
+            .rev()
+            .map(|byte| format!("{:x}.{:x}", byte & 0x0f, byte >> 4))
+            .collect::<Vec<_>>()
+            .join("."),
+    }
+}
+
+/// Default Team Cymru IP-to-ASN zones for IPv4 and IPv6 addresses
+const ASN_ZONE_V4: &str = "origin.asn.cymru.com";
+const ASN_ZONE_V6: &str = "origin6.asn.cymru.com";
+
+/// Origin AS of an address, as returned by `dns_lookup_asn`
+struct AsnInfo {
+    asn: u32,
+    prefix: String,
+    country: Option<String>,
+}
+
+/// Parses a Team Cymru style origin TXT answer
+///
+/// The answer is pipe-delimited, e.g. `15169 | 8.8.8.0/24 | US | arin | 2023-12-28`.
+/// A prefix announced by several ASes lists them space-separated; the first is used.
+///
+/// # Returns
+/// * `Some(AsnInfo)` - The origin AS, prefix and country (None if blank)
+/// * `None` - The answer does not start with an AS number and a prefix
+fn parse_asn_txt(txt: &str) -> Option<AsnInfo> {
+    let mut fields = txt.split('|').map(str::trim);
+    let asn = fields.next()?.split_whitespace().next()?.parse::<u32>().ok()?;
+    let prefix = fields.next().filter(|prefix| parse_cidr(prefix).is_ok())?.to_string();
+    let country = fields.next().filter(|country| !country.is_empty()).map(str::to_uppercase);
+    Some(AsnInfo { asn, prefix, country })
+}
+
+/// Performs an asynchronous IP-to-ASN lookup over DNS
+///
+/// Queries the TXT record of the reversed address under the zone, e.g.
+/// `8.8.8.8.origin.asn.cymru.com` for `8.8.8.8`; IPv6 addresses are reversed nibble by
+/// nibble.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `ip_str` - String containing the IPv4 or IPv6 address to look up
+/// * `zone` - The query zone, None for the Team Cymru zone of the address family
+///
+/// # Returns
+/// * `Ok(AsnInfo)` - The first well-formed answer
+/// * `Err` - Invalid address or zone, no or only malformed answers, or the lookup failed
+async fn dns_lookup_asn_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    ip_str: &str,
+    zone: Option<&str>,
+) -> std::result::Result<AsnInfo, Box<dyn Error>> {
+    let ip_addr = validate_ip(ip_str)?;
+    let zone = match zone {
+        Some(zone) => zone.trim().trim_end_matches('.'),
+        None if ip_addr.is_ipv4() => ASN_ZONE_V4,
+        None => ASN_ZONE_V6,
+    };
+    if zone.is_empty() {
+        return Err("ASN zone must not be empty".into());
+    }
+    // Fully qualified, so search domains are never appended to the query
+    let query = format!("{}.{}.", reversed_labels(&ip_addr), zone);
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, &query, RecordType::TXT, resolver_guard.txt_lookup(query.as_str()))
+        .await
+        .map_err(|e| format!("ASN lookup failed: {}", e))?;
+    lookup
+        .iter()
+        .find_map(|txt| parse_asn_txt(&txt_to_string(txt)))
+        .ok_or_else(|| "No well-formed ASN answer found".into())
+}
+
+/// Checks whether a hostname resolves to any address
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(true)` - At least one A or AAAA record outside the IP filter exists
+/// * `Ok(false)` - NXDOMAIN, no address records, or only filtered addresses
+/// * `Err` - Invalid hostname or the lookup failed (servfail, timeout, network error)
+async fn dns_resolves_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.lookup_ip(hostname)).await {
+        Ok(lookup) => Ok(lookup.iter().any(|ip| !is_filtered_addr(&ip))),
+        Err(e) if is_no_records_error(&e) => Ok(false),
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs an asynchronous A and AAAA lookup, tagging each address with its family
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver, see
+///   `DnsResolverState::dual_stack_resolver` for one returning both families
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(Vec<(&str, String)>)` - `("A", address)` and `("AAAA", address)` pairs outside the
+///   IP filter, IPv4 first, each family in resolver order
+/// * `Err` - Invalid hostname, no A or AAAA records, or the lookup failed
+async fn dns_lookup_addrs_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<(&'static str, String)>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.lookup_ip(hostname))
+        .await
+        .map_err(|e| format!("DNS lookup failed: {}", e))?;
+    let (v4, v6): (Vec<IpAddr>, Vec<IpAddr>) = lookup
+        .iter()
+        .filter(|ip| !is_filtered_addr(ip))
+        .partition(|ip| ip.is_ipv4());
+    Ok(v4
+        .into_iter()
+        .map(|ip| ("A", format_ip_addr(&ip)))
+        .chain(v6.into_iter().map(|ip| ("AAAA", format_ip_addr(&ip))))
+        .collect())
+}
+
+/// An address record as returned by `dns_lookup_detailed`
+struct AddressDetail {
+    address: String,
+    family: &'static str,
+    ttl: u32,
+}
+
+/// Performs an asynchronous A and AAAA lookup, returning each address with its family and TTL
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver, see
+///   `DnsResolverState::dual_stack_resolver` for one returning both families
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(Vec<AddressDetail>)` - The A and AAAA records outside the IP filter in answer
+///   order, with their remaining TTL in seconds (the time left in the cache for cached
+///   answers, 0 once expired)
+/// * `Err` - Invalid hostname, no A or AAAA records, or the lookup failed
+async fn dns_lookup_detailed_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<AddressDetail>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.lookup_ip(hostname))
+        .await
+        .map_err(|e| format!("DNS lookup failed: {}", e))?;
+    // A record never outlives its answer; saturate rather than underflow past expiry
+    let remaining = lookup.valid_until().saturating_duration_since(Instant::now()).as_secs();
+    let remaining = u32::try_from(remaining).unwrap_or(u32::MAX);
+    Ok(lookup
+        .as_lookup()
+        .record_iter()
+        .filter_map(|record| {
+            let (ip, family) = match record.data() {
+                RData::A(a) => (IpAddr::V4(a.0), "A"),
+                RData::AAAA(aaaa) => (IpAddr::V6(aaaa.0), "AAAA"),
+                _ => return None,
+            };
+            if is_filtered_addr(&ip) {
+                return None;
+            }
+            Some(AddressDetail {
+                address: format_ip_addr(&ip),
+                family,
+                ttl: record.ttl().min(remaining),
+            })
+        })
+        .collect())
+}
+
+/// Performs an asynchronous IPv4 lookup reporting whether the answer is DNSSEC-authenticated
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver, see
+///   `DnsResolverState::validating_resolver` for one validating DNSSEC
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok((Vec<String>, bool))` - The IPv4 addresses outside the IP filter and whether every
+///   A record was proven secure; unsigned zones are not authenticated
+/// * `Err` - No IPv4 addresses, the lookup failed, or an A record was proven bogus
+async fn dns_lookup_secure_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<(Vec<String>, bool), Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.ipv4_lookup(hostname))
+        .await
+        .map_err(|e| format!("DNS lookup failed: {}", e))?;
+    let records: Vec<&Record> = lookup
+        .as_lookup()
+        .record_iter()
+        .filter(|record| record.record_type() == RecordType::A)
+        .collect();
+    if records.iter().any(|record| record.proof() == Proof::Bogus) {
+        return Err("DNSSEC validation failed: bogus answer".into());
+    }
+    let authenticated = !records.is_empty() && records.iter().all(|record| record.proof() == Proof::Secure);
+    let addresses: Vec<String> = records
+        .iter()
+        .filter_map(|record| match record.data() {
+            RData::A(a) if !is_filtered_addr(&IpAddr::V4(a.0)) => Some(a.0.to_string()),
+            _ => None,
+        })
+        .collect();
+    if addresses.is_empty() {
+        return Err("No IPv4 addresses found for hostname".into());
+    }
+    Ok((addresses, authenticated))
+}
+
+/// What the resolver queried for a hostname and what it found, as returned by `dns_lookup_debug`
+struct LookupDebug {
+    queried_name: String,
+    canonical_name: Option<String>,
+    addresses: Vec<String>,
+}
+
+/// Performs an asynchronous address lookup, reporting the names the resolver used
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(LookupDebug)` - The name queried after search-domain expansion, the owner name
+///   of the address records at the end of any CNAME chain, and the IPv4 and IPv6
+///   addresses outside the IP filter; a name without address records has no canonical
+///   name and no addresses
+/// * `Err` - Invalid hostname or the lookup failed (servfail, timeout, network error)
+async fn dns_lookup_debug_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<LookupDebug, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.lookup_ip(hostname)).await {
+        Ok(lookup) => {
+            let canonical_name = lookup
+                .as_lookup()
+                .record_iter()
+                .find(|record| matches!(record.data(), RData::A(_) | RData::AAAA(_)))
+                .map(|record| answer_name(record.name()));
+            Ok(LookupDebug {
+                queried_name: answer_name(lookup.query().name()),
+                canonical_name,
+                addresses: lookup
+                    .iter()
+                    .filter(|ip| !is_filtered_addr(ip))
+                    .map(|ip| format_ip_addr(&ip))
+                    .collect(),
+            })
+        }
+        Err(e) => match e.proto().map(|e| e.kind()) {
+            Some(ProtoErrorKind::NoRecordsFound { query, .. }) => Ok(LookupDebug {
+                queried_name: answer_name(query.name()),
+                canonical_name: None,
+                addresses: Vec::new(),
+            }),
+            _ => Err(format!("DNS lookup failed: {}", e).into()),
+        },
+    }
+}
+
+/// Parses a DNS record type string into a RecordType enum
+///
+/// # Arguments
+/// * `record_type_str` - String containing the record type (case-insensitive)
+///
+/// # Supported Types
+/// A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, CAA
+///
+/// # Returns
+/// * `Ok(RecordType)` - Successfully parsed record type
+/// * `Err` - Unsupported or invalid record type
+fn parse_record_type(record_type_str: &str) -> std::result::Result<RecordType, Box<dyn Error>> {
+    let record_type_upper = record_type_str.trim().to_uppercase();
+
+    match record_type_upper.as_str() {
+        "A" => Ok(RecordType::A),
+        "AAAA" => Ok(RecordType::AAAA),
+        "CNAME" => Ok(RecordType::CNAME),
+        "MX" => Ok(RecordType::MX),
+        "NS" => Ok(RecordType::NS),
+        "PTR" => Ok(RecordType::PTR),
+        "SOA" => Ok(RecordType::SOA),
+        "SRV" => Ok(RecordType::SRV),
+        "TXT" => Ok(RecordType::TXT),
+        "CAA" => Ok(RecordType::CAA),
+        _ => Err(format!("Unsupported record type: {}", record_type_str).into()),
+    }
+}
+
+/// Performs an asynchronous DNS lookup for a specific record type, returning the first record
+///
+/// # Arguments
+/// * `resolver` - Reference to the RwLock-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to query
+/// * `record_type` - The DNS record type to query for
+///
+/// # Returns
+/// * `Ok(String)` - The first record of the specified type
+/// * `Err` - No records found or lookup failed
+async fn dns_lookup_with_type_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    record_type: RecordType,
+) -> std::result::Result<String, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, record_type, resolver_guard.lookup(hostname, record_type)).await {
+        Ok(lookup) => {
+            if let Some(record) = lookup.record_iter().find(|record| !is_filtered_record(record.data())) {
+                Ok(format_record_data(record.data()))
+            } else {
+                Err(format!("No {} records found for hostname", record_type).into())
+            }
+        }
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs an asynchronous DNS lookup for a specific record type, returning all records
+///
+/// # Arguments
+/// * `resolver` - Reference to the RwLock-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to query
+/// * `record_type` - The DNS record type to query for
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - All records of the specified type
+/// * `Err` - No records found or lookup failed
+async fn dns_lookup_all_with_type_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    record_type: RecordType,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, record_type, resolver_guard.lookup(hostname, record_type)).await {
+        Ok(lookup) => {
+            if lookup.record_iter().next().is_none() {
+                Err(format!("No {} records found for hostname", record_type).into())
+            } else {
+                // Addresses inside the IP filter are dropped, possibly leaving an empty list
+                Ok(lookup
+                    .record_iter()
+                    .filter(|record| !is_filtered_record(record.data()))
+                    .map(|record| format_record_data(record.data()))
+                    .collect())
+            }
+        }
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs an asynchronous ANY query, returning every record with its type
+///
+/// Many name servers answer ANY queries minimally (RFC 8482), with a single synthesized
+/// HINFO record or only some of the record types of the name, so the result is a
+/// sample of the records rather than all of them.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the name to query
+///
+/// # Returns
+/// * `Ok(Vec<(String, String)>)` - The record type and text-rendered data of each record
+///   in answer order, without addresses in the IP filter
+/// * `Err` - Empty answer or lookup failed
+async fn dns_lookup_any_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<(String, String)>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::ANY, resolver_guard.lookup(hostname, RecordType::ANY)).await {
+        Ok(lookup) => {
+            if lookup.record_iter().next().is_none() {
+                Err("No records found for hostname".into())
+            } else {
+                Ok(lookup
+                    .record_iter()
+                    .filter(|record| !is_filtered_record(record.data()))
+                    .map(|record| (record.record_type().to_string(), format_record_data(record.data())))
+                    .collect())
+            }
+        }
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Matching mode for TXT record token checks
+#[derive(Clone, Copy)]
+enum TxtMatchMode {
+    /// The token must equal a whole TXT record
+    Exact,
+    /// The token may appear anywhere inside a TXT record
+    Substring,
+}
+
+/// Parses a TXT match mode string into a TxtMatchMode enum
+///
+/// # Arguments
+/// * `mode_str` - String containing the mode (case-insensitive): 'exact' or 'substring'
+///
+/// # Returns
+/// * `Ok(TxtMatchMode)` - Successfully parsed mode
+/// * `Err` - Unsupported mode
+fn parse_txt_match_mode(mode_str: &str) -> std::result::Result<TxtMatchMode, Box<dyn Error>> {
+    match mode_str.trim().to_lowercase().as_str() {
+        "exact" => Ok(TxtMatchMode::Exact),
+        "substring" => Ok(TxtMatchMode::Substring),
+        _ => Err(format!("Unsupported match mode '{}'. Supported modes: exact, substring", mode_str).into()),
+    }
+}
+
+/// Performs an asynchronous TXT lookup and checks whether any record matches a token
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to query
+/// * `token` - The token to look for
+/// * `mode` - Whether the token must match a whole record or a substring of one
+///
+/// # Returns
+/// * `Ok(true)` - A TXT record matches the token
+/// * `Ok(false)` - No TXT record matches, or the name has no TXT records
+/// * `Err` - Invalid hostname or the lookup failed
+async fn dns_txt_contains_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    token: &str,
+    mode: TxtMatchMode,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::TXT, resolver_guard.lookup(hostname, RecordType::TXT)).await {
+        Ok(lookup) => Ok(lookup.record_iter().any(|record| {
+            let txt = record.data().to_string();
+            match mode {
+                TxtMatchMode::Exact => txt == token,
+                TxtMatchMode::Substring => txt.contains(token),
+            }
+        })),
+        Err(e) if e.is_no_records_found() => Ok(false),
+        Err(e) => Err(format!("TXT lookup failed: {}", e).into()),
+    }
+}
+
+/// Normalizes a TXT record for comparison
+///
+/// Trims surrounding whitespace and collapses runs of inner whitespace to a single
+/// space, so records that only differ in formatting compare equal.
+fn normalize_txt_record(txt: &str) -> String {
+    txt.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Performs an asynchronous TXT lookup and returns the normalized record set
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to query
+///
+/// # Returns
+/// * `Ok(BTreeSet<String>)` - Normalized TXT records, empty if the name has none
+/// * `Err` - Invalid hostname or the lookup failed
+async fn txt_record_set_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<BTreeSet<String>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::TXT, resolver_guard.lookup(hostname, RecordType::TXT)).await {
+        Ok(lookup) => Ok(lookup
+            .record_iter()
+            .map(|record| normalize_txt_record(&record.data().to_string()))
+            .collect()),
+        Err(e) if e.is_no_records_found() => Ok(BTreeSet::new()),
+        Err(e) => Err(format!("TXT lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs an asynchronous TXT lookup and returns the longest record
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to query
+///
+/// # Returns
+/// * `Ok(Some(String))` - The longest TXT record; the first one wins on ties
+/// * `Ok(None)` - The name has no TXT records
+/// * `Err` - Invalid hostname or the lookup failed
+async fn dns_txt_longest_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Option<String>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, RecordType::TXT, resolver_guard.lookup(hostname, RecordType::TXT)).await {
+        Ok(lookup) => Ok(lookup
+            .record_iter()
+            .map(|record| record.data().to_string())
+            .fold(None, |longest: Option<String>, txt| match longest {
+                Some(longest) if longest.len() >= txt.len() => Some(longest),
+                _ => Some(txt),
+            })),
+        Err(e) if e.is_no_records_found() => Ok(None),
+        Err(e) => Err(format!("TXT lookup failed: {}", e).into()),
+    }
+}
+
+/// Result of comparing the TXT record sets of two hostnames
+struct TxtDiff {
+    /// Records of the second hostname missing from the first
+    added: Vec<String>,
+    /// Records of the first hostname missing from the second
+    removed: Vec<String>,
+}
+
+/// Fetches the TXT records of two hostnames concurrently and diffs them
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname_a` - The baseline hostname
+/// * `hostname_b` - The hostname compared against the baseline
+///
+/// # Returns
+/// * `Ok(TxtDiff)` - Added and removed records, each sorted
+/// * `Err` - Either lookup failed
+async fn dns_txt_diff_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname_a: &str,
+    hostname_b: &str,
+) -> std::result::Result<TxtDiff, Box<dyn Error>> {
+    let (records_a, records_b) = futures::join!(
+        txt_record_set_async(resolver, hostname_a),
+        txt_record_set_async(resolver, hostname_b),
+    );
+    let (records_a, records_b) = (records_a?, records_b?);
+
+    Ok(TxtDiff {
+        added: records_b.difference(&records_a).cloned().collect(),
+        removed: records_a.difference(&records_b).cloned().collect(),
+    })
+}
+
+/// Extracts the DNS response code carried by a resolver error
+///
+/// # Arguments
+/// * `error` - The error returned by the resolver
+///
+/// # Returns
+/// * `Some(ResponseCode)` - The server answered with this response code (e.g. NXDOMAIN, SERVFAIL)
+/// * `None` - No response was received (e.g. timeout or connection failure)
+fn error_response_code(error: &ResolveError) -> Option<ResponseCode> {
+    match error.proto().map(|e| e.kind()) {
+        Some(ProtoErrorKind::NoRecordsFound { response_code, .. }) => Some(*response_code),
+        _ => None,
+    }
+}
+
+/// Formats a DNS response code using its standard mnemonic (e.g. NOERROR, NXDOMAIN)
+///
+/// # Arguments
+/// * `code` - The response code to format
+///
+/// # Returns
+/// The upper-case mnemonic, or `RCODE<n>` for codes without a mnemonic
+fn response_code_name(code: ResponseCode) -> String {
+    match code {
+        ResponseCode::NoError => "NOERROR".to_string(),
+        ResponseCode::FormErr => "FORMERR".to_string(),
+        ResponseCode::ServFail => "SERVFAIL".to_string(),
+        ResponseCode::NXDomain => "NXDOMAIN".to_string(),
+        ResponseCode::NotImp => "NOTIMP".to_string(),
+        ResponseCode::Refused => "REFUSED".to_string(),
+        ResponseCode::YXDomain => "YXDOMAIN".to_string(),
+        ResponseCode::YXRRSet => "YXRRSET".to_string(),
+        ResponseCode::NXRRSet => "NXRRSET".to_string(),
+        ResponseCode::NotAuth => "NOTAUTH".to_string(),
+        ResponseCode::NotZone => "NOTZONE".to_string(),
+        ResponseCode::BADVERS => "BADVERS".to_string(),
+        ResponseCode::BADSIG => "BADSIG".to_string(),
+        ResponseCode::BADKEY => "BADKEY".to_string(),
+        ResponseCode::BADTIME => "BADTIME".to_string(),
+        ResponseCode::BADMODE => "BADMODE".to_string(),
+        ResponseCode::BADNAME => "BADNAME".to_string(),
+        ResponseCode::BADALG => "BADALG".to_string(),
+        ResponseCode::BADTRUNC => "BADTRUNC".to_string(),
+        ResponseCode::BADCOOKIE => "BADCOOKIE".to_string(),
+        other => format!("RCODE{}", u16::from(other)),
+    }
+}
+
+/// Classifies a resolver error into a stable failure string
+///
+/// # Arguments
+/// * `error` - The error returned by the resolver
+/// * `record_type` - The record type that was queried
+///
+/// # Returns
+/// One of `NXDOMAIN`, `no <type> record`, `servfail`, `refused`, `timeout`,
+/// `network error`, another lower-case response code, or `error` for anything else
+fn classify_resolve_error(error: &ResolveError, record_type: RecordType) -> String {
+    match error.proto().map(|e| e.kind()) {
+        Some(ProtoErrorKind::NoRecordsFound { response_code, .. }) => match response_code {
+            ResponseCode::NXDomain => "NXDOMAIN".to_string(),
+            ResponseCode::NoError => format!("no {} record", record_type),
+            ResponseCode::ServFail => "servfail".to_string(),
+            ResponseCode::Refused => "refused".to_string(),
+            other => response_code_name(*other).to_lowercase(),
+        },
+        Some(ProtoErrorKind::RequestRefused) => "refused".to_string(),
+        Some(ProtoErrorKind::Timeout) => "timeout".to_string(),
+        Some(ProtoErrorKind::Io(_) | ProtoErrorKind::NoConnections | ProtoErrorKind::Busy) => "network error".to_string(),
+        _ => "error".to_string(),
+    }
+}
+
+/// Returns true if a resolve error means the name has no records of the queried type
+///
+/// Covers NXDOMAIN and empty NOERROR answers; every other failure (servfail,
+/// timeouts, network errors) leaves the answer unknown.
+fn is_no_records_error(error: &ResolveError) -> bool {
+    matches!(
+        error.proto().map(|e| e.kind()),
+        Some(ProtoErrorKind::NoRecordsFound {
+            response_code: ResponseCode::NXDomain | ResponseCode::NoError,
+            ..
+        })
+    )
+}
+
+/// Performs an asynchronous forward DNS lookup, classifying failures
+///
+/// Resolves like `dns_lookup_async`, but reports why no address was returned.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(String)` - The first IPv4 address found
+/// * `Err(String)` - The classified failure, see `classify_resolve_error`; `no A record`
+///   also covers hosts with only IPv6 addresses, and `filtered` hosts whose IPv4
+///   addresses are all excluded by the IP filter
+async fn dns_lookup_with_status_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<String, String> {
+    let hostname = to_ascii_hostname(hostname).map_err(|_| "invalid hostname".to_string())?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.lookup_ip(hostname))
+        .await
+        .map_err(|e| classify_resolve_error(&e, RecordType::A))?;
+    let mut ipv4_addrs = lookup.iter().filter(IpAddr::is_ipv4).peekable();
+    if ipv4_addrs.peek().is_none() {
+        return Err(format!("no {} record", RecordType::A));
+    }
+    ipv4_addrs
+        .find(|ip| !is_filtered_addr(ip))
+        .map(|ip| ip.to_string())
+        .ok_or_else(|| "filtered".to_string())
+}
+
+/// Coarse lookup statuses reported by `dns_lookup_all_with_status`
+///
+/// # Returns
+/// `nxdomain`, `no_records` (the name exists without records of the queried type),
+/// `timeout`, or `error` for any other failure
+fn lookup_status(error: &ResolveError) -> &'static str {
+    match error.proto().map(|e| e.kind()) {
+        Some(ProtoErrorKind::NoRecordsFound { response_code: ResponseCode::NXDomain, .. }) => "nxdomain",
+        Some(ProtoErrorKind::NoRecordsFound { response_code: ResponseCode::NoError, .. }) => "no_records",
+        Some(ProtoErrorKind::Timeout) => "timeout",
+        _ => "error",
+    }
+}
+
+/// Performs an asynchronous forward DNS lookup of all IPv4 addresses, classifying failures
+///
+/// Resolves like `dns_lookup_all_async`, but reports why no address was returned.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - The IPv4 addresses outside the IP filter, possibly none
+/// * `Err(&str)` - The failure, see `lookup_status`; `no_records` also covers hosts with
+///   only IPv6 addresses, `error` invalid hostnames
+async fn dns_lookup_all_with_status_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<String>, &'static str> {
+    let hostname = to_ascii_hostname(hostname).map_err(|_| "error")?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookup = tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.lookup_ip(hostname))
+        .await
+        .map_err(|e| lookup_status(&e))?;
+    let ips: Vec<IpAddr> = lookup.iter().filter(IpAddr::is_ipv4).collect();
+    if ips.is_empty() {
+        return Err("no_records");
+    }
+    Ok(ips.iter().filter(|ip| !is_filtered_addr(ip)).map(|ip| ip.to_string()).collect())
+}
+
+/// Performs an asynchronous DNS lookup and returns the response code of the answer
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to query
+/// * `record_type` - The DNS record type to query for
+///
+/// # Returns
+/// * `Ok(String)` - The response code mnemonic (e.g. NOERROR, NXDOMAIN, SERVFAIL)
+/// * `Err` - No response was received
+async fn dns_rcode_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    record_type: RecordType,
+) -> std::result::Result<String, Box<dyn Error>> {
+    let hostname = hostname.trim();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, hostname, record_type, resolver_guard.lookup(hostname, record_type)).await {
+        Ok(_) => Ok(response_code_name(ResponseCode::NoError)),
+        Err(e) => match error_response_code(&e) {
+            Some(code) => Ok(response_code_name(code)),
+            None => Err(format!("DNS lookup failed: {}", e).into()),
+        },
+    }
+}
+
+/// Performs an asynchronous forward DNS lookup, returning all IPv4 addresses with
+/// those inside a preferred network first
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+/// * `preferred` - The preferred network
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - All IPv4 addresses, preferred ones first, each group in resolver order
+/// * `Err` - No IPv4 addresses found or lookup failed
+async fn dns_lookup_prefer_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    preferred: Cidr,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let ips = dns_lookup_all_async(resolver, hostname, AddressFamily::Ipv4).await?;
+
+    let (mut inside, outside): (Vec<String>, Vec<String>) = ips.into_iter().partition(|ip| {
+        IpAddr::from_str(ip).is_ok_and(|addr| preferred.contains(&addr))
+    });
+    inside.extend(outside);
+    Ok(inside)
+}
+
+/// Record types queried when profiling a domain's DNS footprint
+const COMMON_RECORD_TYPES: [RecordType; 8] = [
+    RecordType::A,
+    RecordType::AAAA,
+    RecordType::CAA,
+    RecordType::CNAME,
+    RecordType::MX,
+    RecordType::NS,
+    RecordType::SOA,
+    RecordType::TXT,
+];
+
+/// Performs asynchronous DNS lookups for the common record types and counts the records of each
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to query
+///
+/// # Returns
+/// * `Ok(Vec<(String, i32)>)` - Record type and count for each type with at least one record
+/// * `Err` - Invalid hostname, or any of the lookups failed for a reason other than missing records
+async fn dns_record_counts_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<Vec<(String, i32)>, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookups = COMMON_RECORD_TYPES
+        .iter()
+        .map(|record_type| tracked(&resolver_guard, hostname, *record_type, resolver_guard.lookup(hostname, *record_type)));
+    let results = futures::future::join_all(lookups).await;
+
+    let mut counts = Vec::new();
+    for (record_type, result) in COMMON_RECORD_TYPES.iter().zip(results) {
+        let count = match result {
+            // Only count records of the queried type, not CNAMEs followed along the way
+            Ok(lookup) => lookup
+                .record_iter()
+                .filter(|record| record.record_type() == *record_type)
+                .count(),
+            Err(e) if e.is_no_records_found() => 0,
+            Err(e) => return Err(format!("DNS lookup failed: {}", e).into()),
+        };
+        if count > 0 {
+            counts.push((record_type.to_string(), count as i32));
+        }
+    }
+    Ok(counts)
+}
+
+/// Computes the 64-bit FNV-1a hash of a byte string
+///
+/// Used for fingerprints that must stay stable across runs and builds, which the
+/// standard library hashers do not guarantee.
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Performs asynchronous DNS lookups for the common record types and hashes the canonical result
+///
+/// Each record is serialized as `TYPE data` (names in domain-name records lowercased,
+/// TTLs excluded), the lines are sorted and deduplicated, and the result is hashed.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to fingerprint
+///
+/// # Returns
+/// * `Ok(String)` - The fingerprint as 16 lowercase hex digits
+/// * `Err` - Invalid hostname, or any of the lookups failed for a reason other than missing records
+async fn dns_fingerprint_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<String, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let lookups = COMMON_RECORD_TYPES
+        .iter()
+        .map(|record_type| tracked(&resolver_guard, hostname, *record_type, resolver_guard.lookup(hostname, *record_type)));
+    let results = futures::future::join_all(lookups).await;
+
+    let mut lines = BTreeSet::new();
+    for (record_type, result) in COMMON_RECORD_TYPES.iter().zip(results) {
+        match result {
+            Ok(lookup) => {
+                // Only keep records of the queried type, not CNAMEs followed along the way
+                for record in lookup.record_iter().filter(|record| record.record_type() == *record_type) {
+                    let data = record.data().to_string();
+                    let data = match record_type {
+                        RecordType::CNAME | RecordType::MX | RecordType::NS | RecordType::SOA => data.to_lowercase(),
+                        _ => data,
+                    };
+                    lines.insert(format!("{} {}", record_type, data));
+                }
+            }
+            Err(e) if e.is_no_records_found() => {}
+            Err(e) => return Err(format!("DNS lookup failed: {}", e).into()),
+        }
+    }
+
+    let canonical = lines.into_iter().collect::<Vec<_>>().join("\n");
+    Ok(format!("{:016x}", fnv1a_64(canonical.as_bytes())))
+}
+
+/// Normalizes a DNS name for comparison: lowercase without the trailing dot
+fn normalize_name(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_lowercase()
+}
+
+/// Checks whether a name itself owns records of a type
+///
+/// Records reached by following a CNAME belong to another owner and are not counted.
+///
+/// # Returns
+/// * `Ok(bool)` - Whether the name owns records of the type
+/// * `Err` - Lookup failed for a reason other than missing records
+async fn name_owns_records(
+    resolver: &Resolver<TokioConnectionProvider>,
+    name: &str,
+    record_type: RecordType,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let owner = normalize_name(name);
+    match tracked(resolver, name, record_type, resolver.lookup(name, record_type)).await {
+        Ok(lookup) => Ok(lookup.record_iter().any(|record| {
+            record.record_type() == record_type && normalize_name(&record.name().to_string()) == owner
+        })),
+        Err(e) if e.is_no_records_found() => Ok(false),
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Performs asynchronous DNS lookups to detect a CNAME at a zone apex
+///
+/// A name is misconfigured when it owns a CNAME record and also owns the SOA or
+/// NS records that mark it as a zone apex (RFC 1034 forbids other data next to a CNAME).
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `domain` - String containing the domain to check
+///
+/// # Returns
+/// * `Ok(true)` - The domain has a CNAME next to apex records
+/// * `Ok(false)` - The domain is correctly configured
+/// * `Err` - Invalid domain or the lookup failed
+async fn dns_apex_cname_misconfig_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    domain: &str,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let domain = to_ascii_hostname(domain)?;
+    let domain = domain.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let (has_cname, has_soa, has_ns) = futures::join!(
+        name_owns_records(&resolver_guard, domain, RecordType::CNAME),
+        name_owns_records(&resolver_guard, domain, RecordType::SOA),
+        name_owns_records(&resolver_guard, domain, RecordType::NS),
+    );
+
+    Ok(has_cname? && (has_soa? || has_ns?))
+}
+
+/// Delay before starting the next connection attempt (RFC 8305 "Connection Attempt Delay")
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Overall time budget for racing connection attempts
+const CONNECTION_RACE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Validates a TCP/UDP port number argument
+///
+/// # Returns
+/// * `Ok(u16)` - A port between 1 and 65535
+/// * `Err` - Out of range
+fn parse_port(port: i32) -> std::result::Result<u16, Box<dyn Error>> {
+    u16::try_from(port)
+        .ok()
+        .filter(|p| *p > 0)
+        .ok_or_else(|| format!("Invalid port {}: must be between 1 and 65535", port).into())
+}
+
+/// Returns the address family of an IP address as its record type ("A" or "AAAA")
+///
+/// Matches the `family` values of `dns_lookup_addrs` and `dns_lookup_detailed`.
+fn address_family(addr: &IpAddr) -> &'static str {
+    match addr {
+        IpAddr::V4(_) => "A",
+        IpAddr::V6(_) => "AAAA",
+    }
+}
+
+/// Orders addresses for connection racing per RFC 8305, alternating families starting with IPv6
+///
+/// The relative order within each family is preserved.
+fn interleave_address_families(ipv6: Vec<IpAddr>, ipv4: Vec<IpAddr>) -> Vec<IpAddr> {
+    let mut ordered = Vec::with_capacity(ipv6.len() + ipv4.len());
+    let mut ipv6 = ipv6.into_iter();
+    let mut ipv4 = ipv4.into_iter();
+    loop {
+        match (ipv6.next(), ipv4.next()) {
+            (None, None) => return ordered,
+            (v6, v4) => ordered.extend(v6.into_iter().chain(v4)),
+        }
+    }
+}
+
+/// Races TCP connection attempts to a list of addresses, Happy Eyeballs style (RFC 8305)
+///
+/// Attempts start in list order, each one `CONNECTION_ATTEMPT_DELAY` after the previous
+/// one or immediately after the previous one failed. The first established connection wins.
+///
+/// # Arguments
+/// * `addrs` - The addresses to try, in preference order
+/// * `port` - The TCP port to connect to
+///
+/// # Returns
+/// * `Some(IpAddr)` - The address that connected first
+/// * `None` - No address connected within `CONNECTION_RACE_TIMEOUT`
+async fn race_connections(addrs: Vec<IpAddr>, port: u16) -> Option<IpAddr> {
+    let mut remaining = addrs.into_iter().peekable();
+    let mut attempts = FuturesUnordered::new();
+
+    let race = async {
+        loop {
+            if let Some(addr) = remaining.next() {
+                attempts.push(async move {
+                    tokio::net::TcpStream::connect(SocketAddr::new(addr, port))
+                        .await
+                        .map(|_| addr)
+                });
+            }
+            if attempts.is_empty() {
+                return None;
+            }
+
+            // Wait for a winner, a failure, or the delay before the next attempt
+            tokio::select! {
+                result = attempts.next() => {
+                    if let Some(Ok(addr)) = result {
+                        return Some(addr);
+                    }
+                }
+                _ = tokio::time::sleep(CONNECTION_ATTEMPT_DELAY), if remaining.peek().is_some() => {}
+            }
+        }
+    };
+
+    tokio::time::timeout(CONNECTION_RACE_TIMEOUT, race).await.ok().flatten()
+}
+
+/// Performs asynchronous A and AAAA lookups and races connections to the results (RFC 8305)
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+/// * `port` - The TCP port to connect to
+///
+/// # Returns
+/// * `Ok(IpAddr)` - The address that connected first
+/// * `Err` - No addresses found or no connection succeeded
+async fn dns_happy_eyeballs_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    port: u16,
+) -> std::result::Result<IpAddr, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.as_str();
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let (ipv6, ipv4) = futures::join!(
+        tracked(&resolver_guard, hostname, RecordType::AAAA, resolver_guard.ipv6_lookup(hostname)),
+        tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.ipv4_lookup(hostname)),
+    );
+    let ipv6: Vec<IpAddr> = ipv6.map_or(Vec::new(), |lookup| {
+        lookup.iter().map(|aaaa| IpAddr::V6(aaaa.0)).filter(|ip| !is_filtered_addr(ip)).collect()
+    });
+    let ipv4: Vec<IpAddr> = ipv4.map_or(Vec::new(), |lookup| {
+        lookup.iter().map(|a| IpAddr::V4(a.0)).filter(|ip| !is_filtered_addr(ip)).collect()
+    });
+
+    let addrs = interleave_address_families(ipv6, ipv4);
+    if addrs.is_empty() {
+        return Err("No addresses found for hostname".into());
+    }
+
+    race_connections(addrs, port)
+        .await
+        .ok_or_else(|| format!("No connection to {} port {} succeeded", hostname, port).into())
+}
+
+/// Default TCP port probed by `dns_lookup_fastest`
+const FASTEST_PROBE_PORT: u16 = 443;
+
+/// Default time budget of the connection probes of `dns_lookup_fastest`
+const FASTEST_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Performs an asynchronous IPv4 lookup and probes every address for the lowest connect latency
+///
+/// TCP connections to all addresses start at once, so the first one established is
+/// the one with the lowest connect latency. Connections are closed right away.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to resolve
+/// * `port` - The TCP port to connect to
+/// * `timeout` - Time budget of the probes
+///
+/// # Returns
+/// * `Ok(IpAddr)` - The address that connected first
+/// * `Err` - No IPv4 addresses found or no connection succeeded within `timeout`
+async fn dns_lookup_fastest_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+    port: u16,
+    timeout: Duration,
+) -> std::result::Result<IpAddr, Box<dyn Error>> {
+    let addrs = dns_lookup_all_async(resolver, hostname, AddressFamily::Ipv4).await?;
+
+    let mut probes: FuturesUnordered<_> = addrs
+        .iter()
+        .filter_map(|addr| IpAddr::from_str(addr).ok())
+        .map(|addr| async move { TcpStream::connect(SocketAddr::new(addr, port)).await.map(|_| addr) })
+        .collect();
+    let fastest = async {
+        while let Some(result) = probes.next().await {
+            if let Ok(addr) = result {
+                return Some(addr);
+            }
+        }
+        None
+    };
+
+    tokio::time::timeout(timeout, fastest)
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| format!("No connection to {} port {} succeeded", hostname, port).into())
+}
+
+/// Generates a random DNS label that is practically guaranteed not to exist
+fn random_label() -> String {
+    format!("dns-probe-{:016x}", RandomState::new().build_hasher().finish())
+}
+
+/// Performs an asynchronous lookup of a random nonexistent label under a domain to detect a wildcard
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `domain` - String containing the parent domain
+///
+/// # Returns
+/// * `Ok(Some(Vec<String>))` - The domain has a wildcard; the sorted IPv4 addresses it answers with
+/// * `Ok(None)` - The domain has no wildcard
+/// * `Err` - Lookup failed
+async fn dns_wildcard_addresses_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    domain: &str,
+) -> std::result::Result<Option<Vec<String>>, Box<dyn Error>> {
+    let probe = format!("{}.{}", random_label(), domain.trim().trim_end_matches('.'));
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    match tracked(&resolver_guard, &probe, RecordType::A, resolver_guard.ipv4_lookup(probe.as_str())).await {
+        Ok(lookup) => {
+            let mut ips: Vec<String> = lookup.iter().map(|a| a.to_string()).collect();
+            ips.sort();
+            Ok(Some(ips))
+        }
+        Err(e) if e.is_no_records_found() => Ok(None),
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Checks whether a hostname resolves only because of a DNS wildcard
+///
+/// Resolves the hostname and a random nonexistent sibling label under the same parent
+/// concurrently; matching IPv4 address sets mean the answer comes from the wildcard.
+///
+/// # Arguments
+/// * `resolver` - Reference to the ArcSwap-wrapped Hickory DNS resolver
+/// * `hostname` - String containing the hostname to check (e.g., "anything.example.com")
+///
+/// # Returns
+/// * `Ok(true)` - The hostname answers with the same addresses as the random sibling
+/// * `Ok(false)` - The hostname has no addresses, the parent has no wildcard, or the
+///   hostname answers with addresses of its own
+/// * `Err` - Invalid hostname, a single label without parent, or a lookup failed
+async fn dns_is_wildcard_async(
+    resolver: &ArcSwap<Resolver<TokioConnectionProvider>>,
+    hostname: &str,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let hostname = to_ascii_hostname(hostname)?;
+    let hostname = hostname.trim_end_matches('.');
+    let parent = match hostname.split_once('.') {
+        Some((_, parent)) if !parent.is_empty() => parent,
+        _ => return Err(format!("Hostname '{}' has no parent domain", hostname).into()),
+    };
+
+    // Lock-free load of the current resolver
+    let resolver_guard = resolver.load();
+
+    let (lookup, wildcard) = futures::join!(
+        tracked(&resolver_guard, hostname, RecordType::A, resolver_guard.ipv4_lookup(hostname)),
+        dns_wildcard_addresses_async(resolver, parent)
+    );
+    let mut ips: Vec<String> = match lookup {
+        Ok(lookup) => lookup.iter().map(|a| a.to_string()).collect(),
+        Err(e) if is_no_records_error(&e) => return Ok(false),
+        Err(e) => return Err(format!("DNS lookup failed: {}", e).into()),
+    };
+    ips.sort();
+    Ok(wildcard? == Some(ips))
+}
+
+// `parse_list_parameter` reads the C API handle out of `Value`, which wraps nothing else
+const _: () = assert!(std::mem::size_of::<Value>() == std::mem::size_of::<libduckdb_sys::duckdb_value>());
+
+/// Reads a LIST(VARCHAR) table function parameter into its elements
+///
+/// The list children are read through the C API rather than parsed from the text form
+/// of the list, so elements containing `, `, quotes or brackets are kept intact. NULL
+/// and empty elements are skipped.
+fn parse_list_parameter(value: &Value) -> Vec<String> {
+    // SAFETY: `Value` consists of a single `duckdb_value` field (checked above), which
+    // stays owned by `value`; the children are owned and destroyed by their `Value`s
+    unsafe {
+        let list = *(value as *const Value).cast::<libduckdb_sys::duckdb_value>();
+        (0..libduckdb_sys::duckdb_get_list_size(list))
+            .filter_map(|index| {
+                let child = libduckdb_sys::duckdb_get_list_child(list, index);
+                let is_null = child.is_null() || libduckdb_sys::duckdb_is_null_value(child);
+                let element = Value::from(child);
+                (!is_null).then(|| element.to_string().trim().to_string())
+            })
+            .filter(|element| !element.is_empty())
+            .collect()
+    }
+}
+
+/// TTL at or below which answers count as low-TTL for fast-flux scoring (seconds)
+const FAST_FLUX_LOW_TTL: u32 = 300;
+
+/// Maximum number of samples for fast-flux detection
+const FAST_FLUX_MAX_SAMPLES: i32 = 100;
+
+/// Result of a fast-flux analysis
+struct FastFluxResult {
+    flux_score: f64,
+    distinct_ips: i32,
+    min_ttl: u32,
+}
+
+/// Resolves a hostname repeatedly and analyzes how its IPv4 answer set changes
+///
+/// All samples share one resolver whose cache is cleared before each lookup, so that
+/// every answer comes from upstream. Each lookup takes its own permit from the global
+/// concurrency semaphore, so no permit is held during the pauses between samples.
+/// The flux score is the fraction of consecutive samples whose answer sets differ,
+/// weighted by how low the minimum TTL is (weight 1.0 at or below 300 seconds,
+/// `300 / min_ttl` above). It ranges from 0.0 (stable) to 1.0 (every sample changed).
+///
+/// # Arguments
+/// * `hostname` - String containing the hostname to sample
+/// * `samples` - Number of lookups to perform
+/// * `interval` - Pause between consecutive lookups
+///
+/// # Returns
+/// * `Ok(FastFluxResult)` - The flux score, number of distinct IPs and minimum TTL seen
+/// * `Err` - No sample resolved
+async fn dns_fast_flux_async(
+    hostname: &str,
+    samples: u32,
+    interval: Duration,
+) -> std::result::Result<FastFluxResult, Box<dyn Error>> {
+    let hostname = hostname.trim();
+
+    let resolver = GLOBAL_DNS_STATE.uncached_resolver();
+    let mut answer_sets: Vec<Vec<Ipv4Addr>> = Vec::new();
+    let mut min_ttl = u32::MAX;
+    for sample in 0..samples {
+        if sample > 0 {
+            tokio::time::sleep(interval).await;
+        }
+
+        resolver.clear_cache();
+        let lookup = {
+            let semaphore = GLOBAL_DNS_STATE.concurrency_semaphore.load_full();
+            let _permit = semaphore.acquire().await.unwrap();
+            resolver.ipv4_lookup(hostname).await
+        };
+        if let Ok(lookup) = lookup {
+            let mut answers: Vec<Ipv4Addr> = lookup.iter().map(|a| a.0).collect();
+            answers.sort();
+            for record in lookup.as_lookup().record_iter() {
+                if record.record_type() == RecordType::A {
+                    min_ttl = min_ttl.min(record.ttl());
+                }
+            }
+            answer_sets.push(answers);
+        }
+    }
+
+    if answer_sets.is_empty() {
+        return Err("No IPv4 addresses found for hostname".into());
+    }
+
+    let mut distinct: Vec<Ipv4Addr> = answer_sets.iter().flatten().copied().collect();
+    distinct.sort();
+    distinct.dedup();
+
+    let changes = answer_sets.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    let change_rate = if answer_sets.len() > 1 {
+        changes as f64 / (answer_sets.len() - 1) as f64
+    } else {
+        0.0
+    };
+    let ttl_weight = if min_ttl <= FAST_FLUX_LOW_TTL {
+        1.0
+    } else {
+        FAST_FLUX_LOW_TTL as f64 / min_ttl as f64
+    };
+
+    Ok(FastFluxResult {
+        flux_score: change_rate * ttl_weight,
+        distinct_ips: distinct.len() as i32,
+        min_ttl,
+    })
+}
+
+/// Performs an asynchronous lookup and returns the sorted answer set
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - Sorted record data, empty if the name has no records of the type
+/// * `Err` - Lookup failed for a reason other than missing records
+async fn answer_set(
+    resolver: &Resolver<TokioConnectionProvider>,
+    hostname: &str,
+    record_type: RecordType,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    match resolver.lookup(hostname, record_type).await {
+        Ok(lookup) => {
+            let mut records: Vec<String> = lookup
+                .record_iter()
+                .filter(|record| record.record_type() == record_type)
+                .map(|record| format_record_data(record.data()))
+                .collect();
+            records.sort();
+            records.dedup();
+            Ok(records)
+        }
+        Err(e) if e.is_no_records_found() => Ok(Vec::new()),
+        Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
+    }
+}
+
+/// Resolves a name over IPv4 and IPv6 transport concurrently and compares the answers
+///
+/// # Arguments
+/// * `ipv4_resolver` - Resolver reaching only IPv4 name servers
+/// * `ipv6_resolver` - Resolver reaching only IPv6 name servers
+/// * `hostname` - String containing the hostname to query
+/// * `record_type` - The DNS record type to query for
+///
+/// # Returns
+/// * `Ok(bool)` - Whether both transports returned the same answer set
+/// * `Err` - Either transport failed, e.g. no IPv6 connectivity
+async fn dns_dual_stack_consistent_async(
+    ipv4_resolver: &Resolver<TokioConnectionProvider>,
+    ipv6_resolver: &Resolver<TokioConnectionProvider>,
+    hostname: &str,
+    record_type: RecordType,
+) -> std::result::Result<bool, Box<dyn Error>> {
+    let hostname = hostname.trim();
+    let (ipv4_answers, ipv6_answers) = futures::join!(
+        answer_set(ipv4_resolver, hostname, record_type),
+        answer_set(ipv6_resolver, hostname, record_type),
+    );
+    Ok(ipv4_answers? == ipv6_answers?)
+}
+
+/// Legacy generic TLDs delegated before the 2012 new gTLD program
+const LEGACY_GTLDS: [&str; 22] = [
+    "aero", "asia", "biz", "cat", "com", "coop", "edu", "gov", "info", "int", "jobs", "mil", "mobi", "museum",
+    "name", "net", "org", "post", "pro", "tel", "travel", "xxx",
+];
+
+/// Special-use and infrastructure TLDs that are not delegated for general registration
+///
+/// Covers the RFC 6761 names, RFC 7686 `onion`, RFC 6762 `local`, `alt`, `internal`,
+/// the `arpa` infrastructure domain and the names ICANN deferred indefinitely
+/// (`corp`, `home`, `mail`).
+const SPECIAL_TLDS: [&str; 12] = [
+    "alt", "arpa", "corp", "example", "home", "internal", "invalid", "local", "localhost", "mail", "onion", "test",
+];
+
+/// Internationalized country-code TLDs (A-labels), e.g. .рф, .中国, .இந்தியா
+///
+/// The delegated IDN ccTLDs of the Public Suffix List, which maps each to its ISO 3166 code.
+const IDN_CCTLDS: [&str; 59] = [
+    "xn--2scrj9c", "xn--3e0b707e", "xn--3hcrj9c", "xn--45br5cyl", "xn--45brj9c", "xn--54b7fta0cc",
+    "xn--80ao21a", "xn--90a3ac", "xn--90ae", "xn--90ais", "xn--clchc0ea0b2g2a9gcd", "xn--d1alf", "xn--e1a4c",
+    "xn--fiqs8s", "xn--fiqz9s", "xn--fpcrj9c3d", "xn--fzc2c9e2c", "xn--gecrj9c", "xn--h2breg3eve",
+    "xn--h2brj9c", "xn--h2brj9c8c", "xn--j1amh", "xn--j6w193g", "xn--kprw13d", "xn--kpry57d", "xn--l1acc",
+    "xn--lgbbat1ad8j", "xn--mgb9awbf", "xn--mgba3a4f16a", "xn--mgbaam7a8h", "xn--mgbah1a3hjkrd",
+    "xn--mgbai9azgqp6j", "xn--mgbayh7gpa", "xn--mgbbh1a", "xn--mgbbh1a71e", "xn--mgbc0a9azcg",
+    "xn--mgbcpq6gpa1a", "xn--mgberp4a5d4ar", "xn--mgbgu82a", "xn--mgbpl2fh", "xn--mgbtx2b", "xn--mgbx4cd0ab",
+    "xn--mix891f", "xn--node", "xn--o3cw4h", "xn--ogbpf8fl", "xn--p1ai", "xn--pgbs0dh", "xn--q7ce6a",
+    "xn--qxa6a", "xn--qxam", "xn--rvc1e0am3e", "xn--s9brj9c", "xn--wgbh1c", "xn--wgbl6a", "xn--xkc2al3hye2a",
+    "xn--xkc2dl3a5ee0h", "xn--y9a3aq", "xn--ygbi2ammx",
+];
+
+/// TLDs delegated in the DNS root zone, from the embedded copy of the IANA TLD list
+static ROOT_ZONE_TLDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    include_str!("../data/tlds.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+/// Classifies the top-level domain of a hostname
+///
+/// Only the last label decides, so multi-label public suffixes such as `co.uk` are
+/// classified by their TLD (`uk`). Internationalized TLDs are converted to their
+/// A-label first. No network access is needed.
+///
+/// # Returns
+/// * `Some("special")` - Special-use or infrastructure TLD (e.g. `localhost`, `onion`, `arpa`)
+/// * `Some("cctld")` - Delegated country-code TLD: a two-letter TLD or an IDN ccTLD
+/// * `Some("gtld")` - Legacy generic TLD (e.g. `com`, `org`, `edu`)
+/// * `Some("new_gtld")` - Any other delegated TLD, from the new gTLD program (e.g. `app`, `dev`)
+/// * `None` - Empty name, IP address, invalid label, or a TLD that is not in the root zone
+fn classify_tld(hostname: &str) -> Option<&'static str> {
+    let hostname = hostname.trim().trim_end_matches('.');
+    if hostname.is_empty() || IpAddr::from_str(hostname).is_ok() {
+        return None;
+    }
+
+    let tld = hostname.rsplit('.').next()?;
+    let tld = if tld.is_ascii() {
+        tld.to_ascii_lowercase()
+    } else {
+        Label::from_utf8(tld).ok()?.to_ascii().to_ascii_lowercase()
+    };
+    let valid_label = !tld.is_empty()
+        && tld.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !tld.starts_with('-')
+        && !tld.ends_with('-');
+    if !valid_label || tld.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    if SPECIAL_TLDS.contains(&tld.as_str()) {
+        Some("special")
+    } else if !ROOT_ZONE_TLDS.contains(tld.as_str()) {
+        None
+    } else if tld.len() == 2 || IDN_CCTLDS.contains(&tld.as_str()) {
+        Some("cctld")
+    } else if LEGACY_GTLDS.contains(&tld.as_str()) {
+        Some("gtld")
+    } else {
+        Some("new_gtld")
+    }
+}
+
+/// Sends a single DNS query to a name server and returns the complete response message
+///
+/// The resolver only exposes answer records, so functions that need every response
+/// section or the header flags query the name server directly. The query is sent over
+/// UDP and repeated over TCP when the response is truncated. Like dig, it sets the AD
+/// bit so validating name servers report whether the answer is DNSSEC-authenticated.
+///
+/// # Arguments
+/// * `server` - The name server to query
+/// * `hostname` - The name to query (treated as fully qualified)
+/// * `record_type` - The DNS record type to query for
+/// * `timeout` - Timeout for each of the UDP and TCP exchanges
+/// * `edns_buf_size` - UDP payload size advertised via EDNS, or `None` to send a plain
+///   query limited to 512-byte UDP responses
+///
+/// # Returns
+/// * `Ok(Message)` - The response, whatever its response code
+/// * `Err` - Invalid name, network error or timeout
+async fn raw_query(
+    server: SocketAddr,
+    hostname: &str,
+    record_type: RecordType,
+    timeout: Duration,
+    edns_buf_size: Option<u16>,
+) -> std::result::Result<Message, Box<dyn Error>> {
+    let mut name = Name::from_ascii(hostname.trim())?;
+    name.set_fqdn(true);
+
+    let mut query = Message::new();
+    query
+        .set_id(RandomState::new().build_hasher().finish() as u16)
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(true)
+        .set_authentic_data(true)
+        .add_query(Query::query(name, record_type));
+    if let Some(edns_buf_size) = edns_buf_size {
+        let mut edns = Edns::new();
+        edns.set_max_payload(edns_buf_size);
+        query.set_edns(edns);
+    }
+    let request = query.to_vec()?;
+
+    let bind_addr = match server {
+        SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+        SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+    };
+    let udp = async {
+        let socket = UdpSocket::bind(bind_addr).await?;
+        socket.connect(server).await?;
+        socket.send(&request).await?;
+        let mut buf = vec![0u8; usize::from(edns_buf_size.unwrap_or(0)).max(4096)];
+        loop {
+            let len = socket.recv(&mut buf).await?;
+            // Ignore stray datagrams that do not answer this query
+            if let Ok(response) = Message::from_vec(&buf[..len]) {
+                if response.id() == query.id() {
+                    return Ok::<_, Box<dyn Error>>(response);
+                }
+            }
+        }
+    };
+    let response = tokio::time::timeout(timeout, udp).await.map_err(|_| "DNS query timed out")??;
+    if !response.truncated() {
+        return Ok(response);
+    }
+
+    // Truncated over UDP: repeat over TCP with a two-byte length prefix (RFC 1035 4.2.2)
+    let tcp = async {
+        let mut stream = TcpStream::connect(server).await?;
+        stream.write_all(&(request.len() as u16).to_be_bytes()).await?;
+        stream.write_all(&request).await?;
+        let mut len = [0u8; 2];
+        stream.read_exact(&mut len).await?;
+        let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+        stream.read_exact(&mut buf).await?;
+        Ok::<_, Box<dyn Error>>(Message::from_vec(&buf)?)
+    };
+    tokio::time::timeout(timeout, tcp).await.map_err(|_| "DNS query timed out")?
+}
+
+/// A resource record as shown in a dig response section
+struct DigRecord {
+    name: String,
+    record_type: String,
+    ttl: u32,
+    data: String,
+}
+
+impl From<&Record> for DigRecord {
+    fn from(record: &Record) -> Self {
+        DigRecord {
+            name: record.name().to_string(),
+            record_type: record.record_type().to_string(),
+            ttl: record.ttl(),
+            data: format_record_data(record.data()),
+        }
+    }
+}
+
+/// A complete DNS response shaped like dig's output
+struct DigResponse {
+    status: String,
+    flags: Vec<&'static str>,
+    question: Vec<(String, String)>,
+    answer: Vec<DigRecord>,
+    authority: Vec<DigRecord>,
+    additional: Vec<DigRecord>,
+}
+
+impl From<&Message> for DigResponse {
+    fn from(message: &Message) -> Self {
+        let flags = [
+            (message.message_type() == MessageType::Response, "qr"),
+            (message.authoritative(), "aa"),
+            (message.truncated(), "tc"),
+            (message.recursion_desired(), "rd"),
+            (message.recursion_available(), "ra"),
+            (message.authentic_data(), "ad"),
+            (message.checking_disabled(), "cd"),
+        ];
+        DigResponse {
+            status: response_code_name(message.response_code()),
+            flags: flags.iter().filter(|(set, _)| *set).map(|(_, flag)| *flag).collect(),
+            question: message
+                .queries()
+                .iter()
+                .map(|query| (query.name().to_string(), query.query_type().to_string()))
+                .collect(),
+            answer: message.answers().iter().map(DigRecord::from).collect(),
+            authority: message.name_servers().iter().map(DigRecord::from).collect(),
+            additional: message.additionals().iter().map(DigRecord::from).collect(),
+        }
+    }
+}
+
+/// Writes one optional list of dig records per row into a LIST(STRUCT(name, type, ttl, data)) vector
+fn write_dig_records(mut list_vector: ListVector, sections: &[Option<&[DigRecord]>]) {
+    let total_capacity: usize = sections.iter().flatten().map(|records| records.len()).sum();
+    let struct_vector = list_vector.struct_child(total_capacity);
+    let name_vector = struct_vector.child(0, total_capacity);
+    let type_vector = struct_vector.child(1, total_capacity);
+    let mut ttl_vector = struct_vector.child(2, total_capacity);
+    let data_vector = struct_vector.child(3, total_capacity);
+
+    let mut offset = 0;
+    for (i, section) in sections.iter().enumerate() {
+        match section {
+            Some(records) => {
+                list_vector.set_entry(i, offset, records.len());
+                for record in records.iter() {
+                    name_vector.insert(offset, record.name.as_str());
+                    type_vector.insert(offset, record.record_type.as_str());
+                    ttl_vector.as_mut_slice::<u32>()[offset] = record.ttl;
+                    data_vector.insert(offset, record.data.as_str());
+                    offset += 1;
+                }
+            }
+            None => list_vector.set_null(i),
+        }
+    }
+    list_vector.set_len(total_capacity);
+}
+
+/// Reads a VARCHAR column of a data chunk into owned strings
+///
+/// # Arguments
+/// * `input` - The input data chunk
+/// * `column` - Index of the VARCHAR column to read
+///
+/// # Returns
+/// One entry per row, `None` for NULL rows
+fn read_varchar_column(input: &DataChunkHandle, column: usize) -> Vec<Option<String>> {
+    let size = input.len();
+    let vector = input.flat_vector(column);
+    let values = vector.as_slice_with_len::<duckdb_string_t>(size);
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, ptr)| {
+            if vector.row_is_null(i as u64) {
+                None
+            } else {
+                Some(DuckString::new(&mut { *ptr }).as_str().to_string())
+            }
+        })
+        .collect()
+}
+
+/// Reads a fixed-width column (e.g. INTEGER, BOOLEAN) of a data chunk
+///
+/// # Arguments
+/// * `input` - The input data chunk
+/// * `column` - Index of the column to read; `T` must match its physical type
+///
+/// # Returns
+/// One entry per row, `None` for NULL rows
+fn read_column<T: Copy>(input: &DataChunkHandle, column: usize) -> Vec<Option<T>> {
+    let size = input.len();
+    let vector = input.flat_vector(column);
+    let values = vector.as_slice_with_len::<T>(size);
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (!vector.row_is_null(i as u64)).then_some(*value))
+        .collect()
+}
+
+/// Reads a VARCHAR column of host names, domains or addresses to look up
+///
+/// Like `read_varchar_column`, but empty and whitespace-only values are treated as
+/// NULL, so no lookup is issued for them. Names are lowercased, as DNS is
+/// case-insensitive, so differently cased duplicates share cache entries.
+fn read_name_column(input: &DataChunkHandle, column: usize) -> Vec<Option<String>> {
+    read_varchar_column(input, column)
+        .into_iter()
+        .map(|name| name.filter(|name| !name.trim().is_empty()).map(|name| name.to_lowercase()))
+        .collect()
+}
+
+/// Reads the optional `keep_trailing_dot` BOOLEAN column of a name-returning function
+///
+/// # Returns
+/// One flag per row; false for NULL rows or if the column is absent
+fn read_keep_trailing_dot(input: &DataChunkHandle, column: usize) -> Vec<bool> {
+    if input.num_columns() <= column {
+        return vec![false; input.len()];
+    }
+    read_column::<bool>(input, column)
+        .into_iter()
+        .map(|keep| keep.unwrap_or(false))
+        .collect()
+}
+
+/// Formats a resolved domain name (returned without trailing dot) for output
+///
+/// # Arguments
+/// * `name` - The name with its trailing dot stripped
+/// * `keep_trailing_dot` - Whether to return the canonical FQDN form ending in a dot
+fn format_domain_name(name: &str, keep_trailing_dot: bool) -> String {
+    if keep_trailing_dot {
+        format!("{}.", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Sorts IP addresses numerically, so `9.9.9.9` comes before `10.0.0.1`
+///
+/// Values that are not IP addresses sort after all addresses, lexically.
+fn sort_addresses(addresses: &mut [String]) {
+    addresses.sort_by_cached_key(|address| match IpAddr::from_str(address) {
+        Ok(ip) => (false, Some(ip), String::new()),
+        Err(_) => (true, None, address.clone()),
+    });
+}
+
+/// Reads a VARCHAR[] column of a data chunk into owned string lists
+///
+/// # Arguments
+/// * `input` - The input data chunk
+/// * `column` - Index of the VARCHAR[] column
+///
+/// # Returns
+/// One list per row, `None` for NULL rows. NULL list elements are skipped.
+fn read_varchar_list_column(input: &DataChunkHandle, column: usize) -> Vec<Option<Vec<String>>> {
+    let size = input.len();
+    // The validity mask of a list vector is read like that of a flat vector
+    let validity = input.flat_vector(column);
+    let list_vector = input.list_vector(column);
+    let child_len = list_vector.len();
+    let child_vector = list_vector.child(child_len);
+    let values = child_vector.as_slice_with_len::<duckdb_string_t>(child_len);
+
+    (0..size)
+        .map(|i| {
+            if validity.row_is_null(i as u64) {
+                return None;
+            }
+            let (offset, length) = list_vector.get_entry(i);
+            Some(
+                (offset..offset + length)
+                    .filter(|&j| !child_vector.row_is_null(j as u64))
+                    .map(|j| DuckString::new(&mut { values[j] }).as_str().to_string())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Writes one optional list of strings per row into a VARCHAR[] output vector
+///
+/// Every one of the `size` rows is written: rows without a result are set to NULL, so
+/// a short `results` can never leave output rows undefined.
+///
+/// # Arguments
+/// * `output` - The output vector of the scalar function
+/// * `size` - The number of rows in the input chunk
+/// * `results` - One list per row, `None` for NULL rows
+fn write_varchar_list_results(output: &mut dyn WritableVector, size: usize, results: &[Option<Vec<String>>]) {
+    let mut output_vector = output.list_vector();
+
+    // Calculate total number of records for capacity
+    let total_capacity: usize = results.iter().take(size).map(|r| r.as_ref().map_or(0, |v| v.len())).sum();
+    let child_vector = output_vector.child(total_capacity);
+
+    let mut offset = 0;
+    for i in 0..size {
+        match results.get(i).and_then(Option::as_ref) {
+            Some(records) => {
+                output_vector.set_entry(i, offset, records.len());
+                for record in records {
+                    child_vector.insert(offset, record.as_str());
+                    offset += 1;
+                }
+            }
+            None => output_vector.set_null(i),
+        }
+    }
+    output_vector.set_len(total_capacity);
+}
+
+/// Writes one optional list of records per row into a LIST(STRUCT) output vector
+///
+/// Every one of the `size` rows is written: rows without a result are set to NULL, so
+/// a short `results` can never leave output rows undefined.
+///
+/// # Arguments
+/// * `output` - The output vector of the scalar function
+/// * `size` - The number of rows in the input chunk
+/// * `results` - One list per row, `None` for NULL rows
+/// * `num_fields` - The number of fields of the struct
+/// * `write_record` - Writes the fields of a record into the field vectors at an offset
+fn write_struct_list_results<T>(
+    output: &mut dyn WritableVector,
+    size: usize,
+    results: &[Option<Vec<T>>],
+    num_fields: usize,
+    mut write_record: impl FnMut(&mut [FlatVector], usize, &T),
+) {
+    let mut list_vector = output.list_vector();
+    let total_capacity: usize = results.iter().take(size).flatten().map(Vec::len).sum();
+    let struct_vector = list_vector.struct_child(total_capacity);
+    let mut fields: Vec<FlatVector> = (0..num_fields).map(|field| struct_vector.child(field, total_capacity)).collect();
+
+    let mut offset = 0;
+    for i in 0..size {
+        match results.get(i).and_then(Option::as_ref) {
+            Some(records) => {
+                list_vector.set_entry(i, offset, records.len());
+                for record in records {
+                    write_record(&mut fields, offset, record);
+                    offset += 1;
+                }
+            }
+            None => list_vector.set_null(i),
+        }
+    }
+    list_vector.set_len(total_capacity);
+}
+
+/// Writes one optional boolean per row into a BOOLEAN output vector
+///
+/// Every one of the `size` rows is written: rows without a result are set to NULL.
+///
+/// # Arguments
+/// * `output` - The output vector of the scalar function
+/// * `size` - The number of rows in the input chunk
+/// * `results` - One value per row, `None` for NULL rows
+fn write_boolean_results(output: &mut dyn WritableVector, size: usize, results: &[Option<bool>]) {
+    let mut output_vector = output.flat_vector();
+    for i in 0..size {
+        match results.get(i).copied().flatten() {
+            Some(value) => output_vector.as_mut_slice::<bool>()[i] = value,
+            None => output_vector.set_null(i),
+        }
+    }
+}
+
+/// Per-batch metrics of a scalar function, logged at debug level when the batch ends
+///
+/// Only created while debug logging is enabled, so batches pay nothing otherwise.
+/// Cache hits are the session-wide hits counted during the batch, which includes
+/// those of batches running concurrently.
+struct BatchLog {
+    function: &'static str,
+    rows: usize,
+    cache_hits: u64,
+    started: Instant,
+}
+
+impl BatchLog {
+    /// Starts measuring a batch of `rows` rows, or returns `None` if debug logging is off
+    fn start(function: &'static str, rows: usize) -> Option<Self> {
+        log::log_enabled!(log::Level::Debug).then(|| BatchLog {
+            function,
+            rows,
+            cache_hits: GLOBAL_DNS_STATE.cache_stats.hits.load(Ordering::Relaxed),
+            started: Instant::now(),
+        })
+    }
+
+    /// Logs the metrics of the batch, `failures` being the rows whose lookup failed
+    fn finish(self, failures: usize) {
+        log::debug!(
+            "{}: rows={} cache_hits={} failures={} latency_ms={:.3}",
+            self.function,
+            self.rows,
+            GLOBAL_DNS_STATE.cache_stats.hits.load(Ordering::Relaxed).saturating_sub(self.cache_hits),
+            failures,
+            self.started.elapsed().as_secs_f64() * 1000.0,
+        );
+    }
+}
+
+/// Number of lookups currently holding a concurrency permit, and its high-water mark
+#[cfg(test)]
+static IN_FLIGHT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+#[cfg(test)]
+static PEAK_IN_FLIGHT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Counts a lookup as in flight in `run_lookups` until dropped
+#[cfg(test)]
+struct InFlightGuard;
+
+#[cfg(test)]
+impl InFlightGuard {
+    fn enter() -> Self {
+        let in_flight = IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+        PEAK_IN_FLIGHT.fetch_max(in_flight, Ordering::SeqCst);
+        InFlightGuard
+    }
+}
+
+#[cfg(test)]
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Runs one lookup per row concurrently on the shared runtime
+///
+/// Each lookup acquires a permit from the global concurrency semaphore before it runs.
+/// Rows without input (NULL rows) produce `None` without issuing a lookup.
+///
+/// # Arguments
+/// * `inputs` - One optional input per row
+/// * `lookup` - Creates the lookup future for a row's input
+///
+/// # Returns
+/// One optional result per row, in input order
+fn run_lookups<I, T, F, Fut>(inputs: Vec<Option<I>>, lookup: F) -> Vec<Option<T>>
+where
+    F: Fn(I) -> Fut,
+    Fut: std::future::Future<Output = Option<T>>,
+{
+    let semaphore = GLOBAL_DNS_STATE.concurrency_semaphore.load();
+
+    // Single-row chunks (e.g. interactive `SELECT dns_lookup('...')`) await their lookup
+    // directly instead of going through `join_all`
+    if inputs.len() == 1 {
+        let future = inputs.into_iter().next().flatten().map(&lookup);
+        return vec![future.and_then(|future| {
+            GLOBAL_DNS_STATE.runtime.block_on(async {
+                let _permit = semaphore.acquire().await.unwrap();
+                #[cfg(test)]
+                let _in_flight = InFlightGuard::enter();
+                future.await
+            })
+        })];
+    }
+
+    let futures: Vec<_> = inputs
+        .into_iter()
+        .map(|input| {
+            let sem = semaphore.clone();
+            let future = input.map(&lookup);
+            async move {
+                match future {
+                    Some(future) => {
+                        let _permit = sem.acquire().await.unwrap();
+                        #[cfg(test)]
+                        let _in_flight = InFlightGuard::enter();
+                        future.await
+                    }
+                    None => None,
+                }
+            }
+        })
+        .collect();
+
+    GLOBAL_DNS_STATE.runtime.block_on(async { futures::future::join_all(futures).await })
+}
+
+/// Reverse DNS lookup scalar function
+///
+/// Performs reverse DNS lookups by converting IPv4 and IPv6 addresses to hostnames.
+///
+/// # Arguments
+/// * `ip_address` - A VARCHAR containing an IPv4 or IPv6 address (e.g., "8.8.8.8", "2606:4700:4700::1111")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true returns the canonical FQDN form ending in a
+///   dot (default: false, the dot is stripped)
+///
+/// # Returns
+/// * VARCHAR - The resolved hostname (e.g., "dns.google"), or NULL if the lookup fails.
+///   A malformed IP address raises an error for the whole query.
+///
+/// # Example
+/// ```sql
+/// SELECT reverse_dns_lookup('8.8.8.8') as hostname;
+/// -- Returns: dns.google
+///
+/// SELECT reverse_dns_lookup('2606:4700:4700::1111') as hostname;
+/// -- Returns: one.one.one.one
+///
+/// SELECT reverse_dns_lookup('8.8.8.8', true) as fqdn;
+/// -- Returns: dns.google.
+/// ```
+struct ReverseDnsLookup;
+
+impl VScalar for ReverseDnsLookup {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let batch_log = BatchLog::start("reverse_dns_lookup", size);
+        let input_vector = input.flat_vector(0);
+        let mut output_vector = output.flat_vector();
+
+        // Get input strings
+        let values = input_vector.as_slice_with_len::<duckdb_string_t>(size);
+        let strings: Vec<String> = values
+            .iter()
+            .map(|ptr| DuckString::new(&mut { *ptr }).as_str().to_string())
+            .collect();
+
+        // A malformed address is a mistake in the query, not a lookup failure: fail the
+        // whole query instead of returning NULL
+        for (i, ip_address) in strings.iter().enumerate() {
+            if !input_vector.row_is_null(i as u64) && !ip_address.trim().is_empty() {
+                validate_ip(ip_address)?;
+            }
+        }
+
+        let inputs: Vec<Option<String>> = strings
+            .iter()
+            .enumerate()
+            .map(|(i, ip_address)| {
+                (!input_vector.row_is_null(i as u64) && !ip_address.trim().is_empty()).then(|| ip_address.clone())
+            })
+            .collect();
+
+        // Use the global resolver state - load once for all lookups
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let hostnames = run_lookups(inputs, |ip_address| async move {
+            reverse_dns_lookup_async(resolver, &ip_address).await.ok()
+        });
+
+        // Write results to output
+        let keep_trailing_dot = read_keep_trailing_dot(input, 1);
+        for (i, hostname) in hostnames.iter().enumerate() {
+            match hostname {
+                Some(hostname) => output_vector.insert(i, format_domain_name(hostname, keep_trailing_dot[i]).as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        if let Some(batch_log) = batch_log {
+            let failures = (0..size)
+                .filter(|&i| hostnames[i].is_none())
+                .filter(|&i| !input_vector.row_is_null(i as u64) && !strings[i].trim().is_empty())
+                .count();
+            batch_log.finish(failures);
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+        ]
+    }
+}
+
+/// Forward DNS lookup scalar function
+///
+/// Performs DNS lookups to resolve hostnames to IP addresses or other DNS record types.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+/// * `record_type` - Optional VARCHAR specifying the DNS record type (e.g., "A", "MX", "TXT", "CNAME").
+///   In the two-argument form, a name server address (e.g., "1.1.1.1" or "9.9.9.9:53")
+///   resolves the first IPv4 address through that server, 'udp' or 'tcp' through the session
+///   name servers over only that transport; 'first', 'random' or 'join' select the IPv4
+///   address returned (the first, one picked uniformly per row, or all comma-separated);
+///   an empty string uses the defaults. Any other value raises an error
+/// * `resolver_spec` - Optional VARCHAR overriding the session resolver for this call
+///   (e.g., "1.1.1.1,8.8.8.8;tcp;timeout=2"), see `parse_resolver_spec`
+/// * `timeout_ms` - Optional INTEGER in place of `record_type`: time budget of the lookup
+///   of the first IPv4 address in milliseconds; rows exceeding it return NULL
+/// * `fqdn_only` - Optional BOOLEAN in place of `record_type`: `true` resolves the name
+///   as fully qualified, without appending search domains
+/// * `nameservers` - Optional VARCHAR[] in place of `record_type`: resolves the first IPv4
+///   address through these name servers, failing over between them
+///
+/// # Returns
+/// * VARCHAR - The first resolved record:
+///   - Without record_type: First IPv4 address, or of the family set by
+///     `set_dns_address_family`
+///   - With record_type: First record of specified type
+///   - Returns NULL on error
+///
+/// # Supported Record Types
+/// A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, CAA
+///
+/// # Examples
+/// ```sql
+/// -- Get first IPv4 address
+/// SELECT dns_lookup('google.com') as ip;
+/// -- Returns: 142.251.209.142
+///
+/// -- Get TXT record
+/// SELECT dns_lookup('_dmarc.google.com', 'TXT') as txt;
+/// -- Returns: v=DMARC1; p=reject; ...
+///
+/// -- Get MX record
+/// SELECT dns_lookup('google.com', 'MX') as mx;
+/// -- Returns: 10 smtp.google.com.
+///
+/// -- Resolve through a specific name server
+/// SELECT dns_lookup('google.com', '1.1.1.1') as ip;
+///
+/// -- Query over TCP end-to-end
+/// SELECT dns_lookup('google.com', 'tcp') as ip;
+///
+/// -- Spread rows over the addresses of a round-robin name
+/// SELECT dns_lookup('google.com', 'random') as ip;
+///
+/// -- Give up on slow or dead domains after 500 ms
+/// SELECT dns_lookup('google.com', 500) as ip;
+///
+/// -- Never append resolv.conf search domains
+/// SELECT dns_lookup('intranet', true) as ip;
+///
+/// -- Fail over to a secondary name server
+/// SELECT dns_lookup('google.com', ['10.0.0.53', '1.1.1.1']) as ip;
+///
+/// -- Resolve through specific name servers for this call only
+/// SELECT dns_lookup('google.com', 'A', '1.1.1.1;tcp') as ip;
+/// ```
+struct DnsLookup;
+
+impl VScalar for DnsLookup {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let batch_log = BatchLog::start("dns_lookup", size);
+        let hostname_vector = input.flat_vector(0);
+        let mut output_vector = output.flat_vector();
+
+        // Get hostname strings
+        let hostname_values = hostname_vector.as_slice_with_len::<duckdb_string_t>(size);
+        // Lowercased so differently cased duplicates collapse into one lookup
+        let mut hostnames: Vec<String> = hostname_values
+            .iter()
+            .map(|ptr| DuckString::new(&mut { *ptr }).as_str().to_lowercase())
+            .collect();
+
+        // A second INTEGER parameter is a per-row timeout in milliseconds
+        let has_timeout = input.num_columns() == 2
+            && input.flat_vector(1).logical_type().id() == LogicalTypeId::Integer;
+
+        // A second VARCHAR[] parameter lists the name servers to resolve through
+        let has_nameservers = input.num_columns() == 2
+            && input.flat_vector(1).logical_type().id() == LogicalTypeId::List;
+
+        // A second BOOLEAN parameter forces fully qualified lookups: the trailing dot
+        // makes the resolver treat the name as absolute, skipping search domains
+        let has_fqdn_only = input.num_columns() == 2
+            && input.flat_vector(1).logical_type().id() == LogicalTypeId::Boolean;
+        if has_fqdn_only {
+            for (hostname, fqdn_only) in hostnames.iter_mut().zip(read_column::<bool>(input, 1)) {
+                let trimmed = hostname.trim();
+                if fqdn_only == Some(true) && !trimmed.is_empty() && !trimmed.ends_with('.') {
+                    *hostname = format!("{}.", trimmed);
+                }
+            }
+        }
+        let timeouts: Vec<Option<Duration>> = if has_timeout {
+            read_column::<i32>(input, 1)
+                .into_iter()
+                .map(|timeout_ms| match timeout_ms {
+                    Some(ms) if ms <= 0 => Err(format!("Timeout must be greater than 0 ms, got {}", ms)),
+                    Some(ms) => Ok(Some(Duration::from_millis(ms as u64))),
+                    None => Ok(None),
+                })
+                .collect::<std::result::Result<_, _>>()?
+        } else {
+            vec![None; size]
+        };
+
+        // Check if we have a second parameter (record_type)
+        let has_record_type = input.num_columns() > 1 && !has_timeout && !has_fqdn_only && !has_nameservers;
+        let mut record_types: Option<Vec<Option<String>>> = if has_record_type {
+            let record_type_vector = input.flat_vector(1);
+            let record_type_values = record_type_vector.as_slice_with_len::<duckdb_string_t>(size);
+            Some(
+                (0..size)
+                    .map(|i| {
+                        if record_type_vector.row_is_null(i as u64) {
+                            None
+                        } else {
+                            Some(
+                                DuckString::new(&mut { record_type_values[i] })
+                                    .as_str()
+                                    .to_string(),
+                            )
+                        }
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        // Optional third parameter (resolver_spec) overrides the session resolver per row
+        let mut spec_resolvers = read_spec_resolvers(input, 2)?;
+        if has_nameservers {
+            for (servers, spec_resolver) in read_varchar_list_column(input, 1).into_iter().zip(spec_resolvers.iter_mut()) {
+                if let Some(servers) = servers {
+                    let (spec, key) = nameserver_list_spec(&servers)?;
+                    *spec_resolver = Some(GLOBAL_DNS_STATE.spec_resolver_with_key(&key, &spec)?);
+                }
+            }
+        }
+
+        // With two arguments, the second one may name a transport, an address strategy or a
+        // name server instead of a record type
+        let mut strategies = vec![AddressStrategy::First; size];
+        if let (2, Some(record_types)) = (input.num_columns(), record_types.as_mut()) {
+            for ((record_type, spec_resolver), strategy) in
+                record_types.iter_mut().zip(spec_resolvers.iter_mut()).zip(strategies.iter_mut())
+            {
+                let Some(value) = record_type.as_deref().map(|value| value.trim().to_string()) else {
+                    continue;
+                };
+                if value.is_empty() {
+                    *record_type = None;
+                } else if let Some(address_strategy) = parse_address_strategy(&value) {
+                    *strategy = address_strategy;
+                    *record_type = None;
+                } else if let Some(protocol) = parse_transport_arg(&value) {
+                    *spec_resolver = Some(GLOBAL_DNS_STATE.transport_resolver(protocol)?);
+                    *record_type = None;
+                } else if is_nameserver_arg(&value) {
+                    *spec_resolver = Some(GLOBAL_DNS_STATE.spec_resolver(&value)?);
+                    *record_type = None;
+                } else if parse_record_type(&value).is_err() {
+                    return Err(format!(
+                        "Unknown dns_lookup argument '{}'. Expected a record type, an address strategy \
+                         (first, random, join), a transport (udp, tcp) or a name server address",
+                        value
+                    )
+                    .into());
+                }
+            }
+        }
+
+        // Rows with a timeout resolve through a copy of the session resolver using it
+        for (timeout, spec_resolver) in timeouts.iter().zip(spec_resolvers.iter_mut()) {
+            if let Some(timeout) = timeout {
+                *spec_resolver = Some(GLOBAL_DNS_STATE.timeout_resolver(*timeout)?);
+            }
+        }
+
+        // Use the global resolver state - load once for all lookups
+        let session_resolver = &GLOBAL_DNS_STATE.resolver;
+        let family = **GLOBAL_DNS_STATE.address_family.load();
+
+        // Deduplicate identical lookups so repeated hostnames are resolved once per chunk.
+        // `unique_rows` holds the first row of each distinct lookup; every row maps to an
+        // index into it, NULL and empty hostnames to None.
+        // Rows picking among all addresses share one lookup of every address.
+        type LookupKey<'a> = (&'a str, Option<&'a str>, Option<usize>, Option<Duration>, bool);
+        let mut unique_rows: Vec<usize> = Vec::new();
+        let mut unique_index: HashMap<LookupKey, usize> = HashMap::new();
+        let row_lookups: Vec<Option<usize>> = (0..size)
+            .map(|i| {
+                if hostname_vector.row_is_null(i as u64) || hostnames[i].trim().is_empty() {
+                    return None;
+                }
+                let key = (
+                    hostnames[i].as_str(),
+                    record_types.as_ref().and_then(|rt| rt[i].as_deref()),
+                    spec_resolvers[i].as_ref().map(|resolver| Arc::as_ptr(resolver) as usize),
+                    timeouts[i],
+                    strategies[i] != AddressStrategy::First,
+                );
+                let index = *unique_index.entry(key).or_insert_with(|| {
+                    unique_rows.push(i);
+                    unique_rows.len() - 1
+                });
+                Some(index)
+            })
+            .collect();
+
+        // Process all unique lookups concurrently with semaphore-controlled execution
+        let results = run_lookups(unique_rows.iter().map(|&i| Some(i)).collect(), |i| {
+            let hostname = hostnames[i].clone();
+            let record_type_opt = record_types.as_ref().and_then(|rt| rt[i].clone());
+            let resolver = spec_resolvers[i].clone();
+            let timeout = timeouts[i];
+            let all_addresses = strategies[i] != AddressStrategy::First;
+            async move {
+                let resolver = resolver.as_deref().unwrap_or(session_resolver);
+                let lookup = async {
+                    if let Some(record_type_str) = record_type_opt {
+                        match parse_record_type(&record_type_str) {
+                            Ok(record_type) => dns_lookup_with_type_async(resolver, &hostname, record_type)
+                                .await
+                                .map(|record| vec![record]),
+                            Err(e) => Err(e),
+                        }
+                    } else if all_addresses {
+                        dns_lookup_all_async(resolver, &hostname, family).await
+                    } else {
+                        dns_lookup_async(resolver, &hostname, family).await.map(|address| vec![address])
+                    }
+                };
+                // The timeout bounds the row even if the resolver overruns its own
+                match timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, lookup).await.ok().and_then(|r| r.ok()),
+                    None => lookup.await.ok(),
+                }
+            }
+        });
+
+        // Fan results back out to the original row positions, picking each row's address
+        let mut failures = 0;
+        for (i, lookup) in row_lookups.iter().enumerate() {
+            match lookup.and_then(|index| results.get(index)?.as_ref()).and_then(|data| strategies[i].pick(data)) {
+                Some(data) => output_vector.insert(i, data.as_str()),
+                None => {
+                    failures += usize::from(lookup.is_some());
+                    output_vector.set_null(i);
+                }
+            }
+        }
+        if let Some(batch_log) = batch_log {
+            batch_log.finish(failures);
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Integer),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+        ]
+    }
+}
+
+/// Forward DNS lookup scalar function (returns all records)
+///
+/// Performs DNS lookups to resolve hostnames to all matching IP addresses or DNS records.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+/// * `record_type` - Optional VARCHAR specifying the DNS record type (e.g., "A", "MX", "TXT", "NS")
+/// * `resolver_spec` - Optional VARCHAR overriding the session resolver for this call
+///   (e.g., "1.1.1.1,8.8.8.8;tcp;timeout=2"), see `parse_resolver_spec`
+/// * `sorted` - Optional BOOLEAN in place of `record_type`: `true` returns the IPv4
+///   addresses in numeric order instead of resolver order
+/// * `max_results` - Optional INTEGER in place of `record_type`: returns at most this
+///   many IPv4 addresses, in resolver order; values <= 0 raise an error
+///
+/// # Returns
+/// * VARCHAR[] - An array of all resolved records:
+///   - Without record_type: All IPv4 addresses, or of the family set by
+///     `set_dns_address_family`
+///   - With record_type: All records of specified type
+///   - Returns NULL on error
+///
+/// # Supported Record Types
+/// A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, CAA
+///
+/// # Examples
+/// ```sql
+/// -- Get all IPv4 addresses
+/// SELECT dns_lookup_all('google.com') as ips;
+/// -- Returns: [142.251.209.142, ...]
+///
+/// -- Get all MX records
+/// SELECT dns_lookup_all('google.com', 'MX') as mx_records;
+/// -- Returns: [10 smtp.google.com., ...]
+///
+/// -- Get all TXT records
+/// SELECT dns_lookup_all('google.com', 'TXT') as txt_records;
+/// -- Returns: [v=spf1 include:_spf.google.com ~all, ...]
+///
+/// -- Get all IPv4 addresses in a stable order
+/// SELECT dns_lookup_all('google.com', true) as ips;
+///
+/// -- Keep at most two addresses of a CDN name
+/// SELECT dns_lookup_all('google.com', 2) as ips;
+/// ```
+struct DnsLookupAll;
+
+impl VScalar for DnsLookupAll {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let batch_log = BatchLog::start("dns_lookup_all", size);
+        let hostname_vector = input.flat_vector(0);
+
+        // Get hostname strings
+        let hostname_values = hostname_vector.as_slice_with_len::<duckdb_string_t>(size);
+        let hostnames: Vec<String> = hostname_values
+            .iter()
+            .map(|ptr| DuckString::new(&mut { *ptr }).as_str().to_string())
+            .collect();
+
+        // A second BOOLEAN parameter sorts the addresses numerically
+        let has_sorted = input.num_columns() == 2
+            && input.flat_vector(1).logical_type().id() == LogicalTypeId::Boolean;
+        let sorted: Vec<bool> = if has_sorted {
+            read_column::<bool>(input, 1).into_iter().map(|sorted| sorted == Some(true)).collect()
+        } else {
+            vec![false; size]
+        };
+
+        // A second INTEGER parameter caps the number of addresses returned per row
+        let has_max_results = input.num_columns() == 2
+            && input.flat_vector(1).logical_type().id() == LogicalTypeId::Integer;
+        let max_results: Vec<Option<usize>> = if has_max_results {
+            read_column::<i32>(input, 1)
+                .into_iter()
+                .map(|max_results| match max_results {
+                    Some(max) if max <= 0 => Err(format!("max_results must be greater than 0, got {}", max)),
+                    Some(max) => Ok(Some(max as usize)),
+                    None => Ok(None),
+                })
+                .collect::<std::result::Result<_, _>>()?
+        } else {
+            vec![None; size]
+        };
+
+        // Check if we have a second parameter (record_type)
+        let record_types: Option<Vec<Option<String>>> = if input.num_columns() > 1 && !has_sorted && !has_max_results {
+            let record_type_vector = input.flat_vector(1);
+            let record_type_values = record_type_vector.as_slice_with_len::<duckdb_string_t>(size);
+            Some(
+                (0..size)
+                    .map(|i| {
+                        if record_type_vector.row_is_null(i as u64) {
+                            None
+                        } else {
+                            Some(
+                                DuckString::new(&mut { record_type_values[i] })
+                                    .as_str()
+                                    .to_string(),
+                            )
+                        }
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        // Optional third parameter (resolver_spec) overrides the session resolver per row
+        let spec_resolvers = read_spec_resolvers(input, 2)?;
+
+        // Use the global resolver state - load once for all lookups
+        let session_resolver = &GLOBAL_DNS_STATE.resolver;
+        let family = **GLOBAL_DNS_STATE.address_family.load();
+
+        // Process all lookups concurrently with semaphore-controlled execution
+        let rows: Vec<Option<usize>> = (0..size)
+            .map(|i| (!hostname_vector.row_is_null(i as u64) && !hostnames[i].trim().is_empty()).then_some(i))
+            .collect();
+        let mut all_results = run_lookups(rows, |i| {
+            let hostname = hostnames[i].clone();
+            let record_type_opt = record_types.as_ref().and_then(|rt| rt[i].clone());
+            let resolver = spec_resolvers[i].clone();
+            async move {
+                let resolver = resolver.as_deref().unwrap_or(session_resolver);
+                let result = if let Some(record_type_str) = record_type_opt {
+                    match parse_record_type(&record_type_str) {
+                        Ok(record_type) => dns_lookup_all_with_type_async(resolver, &hostname, record_type).await,
+                        Err(e) => Err(e),
+                    }
+                } else {
+                    dns_lookup_all_async(resolver, &hostname, family).await
+                };
+                result.ok()
+            }
+        });
+        for ((result, sorted), max_results) in all_results.iter_mut().zip(sorted).zip(max_results) {
+            if let (Some(addresses), true) = (result.as_mut(), sorted) {
+                sort_addresses(addresses);
+            }
+            if let (Some(addresses), Some(max_results)) = (result.as_mut(), max_results) {
+                addresses.truncate(max_results);
+            }
+        }
+
+        write_varchar_list_results(output, size, &all_results);
+        if let Some(batch_log) = batch_log {
+            let failures = (0..size)
+                .filter(|&i| all_results[i].is_none())
+                .filter(|&i| !hostname_vector.row_is_null(i as u64) && !hostnames[i].trim().is_empty())
+                .count();
+            batch_log.finish(failures);
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Integer),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+        ]
+    }
+}
+
+/// IPv6 forward DNS lookup scalar function
+///
+/// Resolves a hostname to its first IPv6 address.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * VARCHAR - The first IPv6 address in compressed notation, or NULL if the host
+///   has no AAAA records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_aaaa('google.com');
+/// -- Returns: 2a00:1450:4001:82b::200e
+/// ```
+struct DnsLookupAaaa;
+
+impl VScalar for DnsLookupAaaa {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_aaaa_all_async(resolver, &hostname).await.ok()?.into_iter().next()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(ip) => output_vector.insert(i, ip.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// IPv6 forward DNS lookup scalar function (returns all addresses)
+///
+/// Resolves a hostname to all its IPv6 addresses.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * VARCHAR[] - All IPv6 addresses in compressed notation, or NULL if the host has
+///   no AAAA records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_aaaa_all('cloudflare.com');
+/// -- Returns: [2606:4700::6810:84e5, 2606:4700::6810:85e5]
+/// ```
+struct DnsLookupAaaaAll;
+
+impl VScalar for DnsLookupAaaaAll {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_aaaa_all_async(resolver, &hostname).await.ok()
+        });
+
+        write_varchar_list_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        )]
+    }
+}
+
+/// Generic DNS query scalar function
+///
+/// Queries any supported record type and returns the records in their canonical
+/// text form, for record types without a dedicated function.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to query (e.g., "google.com")
+/// * `record_type` - A VARCHAR specifying the DNS record type (case-insensitive)
+///
+/// # Returns
+/// * VARCHAR[] - All records of the type, or NULL if none exist or the lookup fails.
+///   An unsupported record type raises an error.
+///
+/// # Supported Record Types
+/// A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, CAA
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_query('google.com', 'soa');
+/// -- Returns: [ns1.google.com. dns-admin.google.com. 123456789 900 900 1800 60]
+/// ```
+struct DnsQuery;
+
+impl VScalar for DnsQuery {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let record_types = read_varchar_column(input, 1)
+            .into_iter()
+            .map(|record_type| record_type.map(|rt| parse_record_type(&rt)).transpose())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(record_types)
+            .map(|(hostname, record_type)| Some((hostname?, record_type?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname, record_type)| async move {
+            dns_lookup_all_with_type_async(resolver, &hostname, record_type).await.ok()
+        });
+
+        write_varchar_list_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ],
+            LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        )]
+    }
+}
+
+/// ANY lookup scalar function
+///
+/// Issues an ANY query and returns whatever records the name server includes, broken
+/// out by type, for quick reconnaissance of a name.
+///
+/// # Arguments
+/// * `name` - A VARCHAR containing the name to query (e.g., "cloudflare.com")
+///
+/// # Returns
+/// * STRUCT(record_type VARCHAR, value VARCHAR)[] - One element per record in answer
+///   order, or NULL if the answer is empty or the lookup fails. Name servers following
+///   RFC 8482 answer with a single HINFO record (e.g. `"RFC8482" ""`) instead.
+///
+/// # Examples
+/// ```sql
+/// SELECT unnest(dns_lookup_any('example.com'));
+/// -- Returns: {'record_type': A, 'value': 93.184.215.14}, {'record_type': NS, ...}, ...
+/// ```
+struct DnsLookupAny;
+
+impl VScalar for DnsLookupAny {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let names = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(names, |name| async move { dns_lookup_any_async(resolver, &name).await.ok() });
+
+        write_struct_list_results(output, input.len(), &results, 2, |fields, offset, (record_type, value)| {
+            fields[0].insert(offset, record_type.as_str());
+            fields[1].insert(offset, value.as_str());
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("record_type", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("value", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ])),
+        )]
+    }
+}
+
+/// CNAME lookup scalar function
+///
+/// Follows the CNAME chain of a hostname and returns its canonical target, e.g. to
+/// audit which custom domains still point at an expected CNAME target.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "www.github.com")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true keeps the trailing FQDN dot (default: false)
+///
+/// # Returns
+/// * VARCHAR - The final target of the CNAME chain without the trailing dot, or NULL if
+///   the name has no CNAME (e.g. it owns A/AAAA records directly) or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_cname('www.github.com');
+/// -- Returns: github.com
+///
+/// SELECT dns_lookup_cname('github.com');
+/// -- Returns: NULL
+/// ```
+struct DnsLookupCname;
+
+impl VScalar for DnsLookupCname {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_cname_async(resolver, &hostname).await.ok().flatten()
+        });
+
+        let keep_trailing_dot = read_keep_trailing_dot(input, 1);
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(target) => output_vector.insert(i, format_domain_name(target, keep_trailing_dot[i]).as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+        ]
+    }
+}
+
+/// MX lookup scalar function
+///
+/// Returns the mail exchangers of a domain, primary first.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the domain to resolve (e.g., "google.com")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true keeps the trailing FQDN dot (default: false)
+///
+/// # Returns
+/// * STRUCT(preference USMALLINT, exchange VARCHAR)[] - One element per MX record sorted
+///   by preference ascending, or NULL if the domain has no MX records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT (dns_lookup_mx('google.com'))[1].exchange;
+/// -- Returns: smtp.google.com
+/// ```
+struct DnsLookupMx;
+
+impl VScalar for DnsLookupMx {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_mx_async(resolver, &hostname).await.ok()
+        });
+        let results: Vec<_> = results
+            .into_iter()
+            .zip(read_keep_trailing_dot(input, 1))
+            .map(|(records, keep)| {
+                records.map(|records| {
+                    records
+                        .into_iter()
+                        .map(|(preference, exchange)| (preference, format_domain_name(&exchange, keep)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        write_struct_list_results(output, input.len(), &results, 2, |fields, offset, (preference, exchange)| {
+            fields[0].as_mut_slice::<u16>()[offset] = *preference;
+            fields[1].insert(offset, exchange.as_str());
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        let returns = || {
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("preference", LogicalTypeHandle::from(LogicalTypeId::USmallint)),
+                ("exchange", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ]))
+        };
+        vec![
+            ScalarFunctionSignature::exact(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)], returns()),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                returns(),
+            ),
+        ]
+    }
+}
+
+/// MX hostname lookup scalar function
+///
+/// Returns only the mail exchanger hostnames of a domain, primary first, for callers
+/// that do not need the preferences of `dns_lookup_mx`.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain to resolve (e.g., "google.com")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true keeps the trailing FQDN dot (default: false)
+///
+/// # Returns
+/// * VARCHAR[] - The exchange hostnames sorted by preference ascending, or NULL if the
+///   domain has no MX records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_mx_hosts('google.com');
+/// -- Returns: [smtp.google.com]
+/// ```
+struct DnsLookupMxHosts;
+
+impl VScalar for DnsLookupMxHosts {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+        let inputs: Vec<_> = domains
+            .into_iter()
+            .zip(read_keep_trailing_dot(input, 1))
+            .map(|(domain, keep)| Some((domain?, keep)))
+            .collect();
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(inputs, |(domain, keep)| async move {
+            let records = dns_lookup_mx_async(resolver, &domain).await.ok()?;
+            Some(records.iter().map(|(_, exchange)| format_domain_name(exchange, keep)).collect::<Vec<_>>())
+        });
+
+        write_varchar_list_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+        ]
+    }
+}
+
+/// TXT lookup scalar function
+///
+/// Returns every TXT record of a hostname, with multi-string records joined back together.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+/// * `edns_buf_size` - Optional INTEGER UDP payload size (512-65535) to advertise via EDNS,
+///   querying the first plain name server directly so large records are not truncated
+///
+/// # Returns
+/// * VARCHAR[] - One element per TXT record, or NULL if the host has no TXT records or
+///   the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_txt('google.com');
+/// -- Returns: [v=spf1 include:_spf.google.com ~all, ...]
+///
+/// -- Fetch a long DKIM key in a single UDP response
+/// SELECT dns_lookup_txt('google._domainkey.example.com', 4096);
+/// ```
+struct DnsLookupTxt;
+
+impl VScalar for DnsLookupTxt {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let results = if input.num_columns() > 1 {
+            let buf_sizes = read_column::<i32>(input, 1)
+                .into_iter()
+                .map(|size| match size {
+                    Some(size) if !EDNS_BUF_SIZE_RANGE.contains(&size) => Err(format!(
+                        "EDNS buffer size must be between {} and {} bytes, got {}",
+                        EDNS_BUF_SIZE_RANGE.start(),
+                        EDNS_BUF_SIZE_RANGE.end(),
+                        size
+                    )),
+                    size => Ok(size.map(|size| size as u16)),
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let inputs: Vec<_> = hostnames
+                .into_iter()
+                .zip(buf_sizes)
+                .map(|(hostname, buf_size)| Some((hostname?, buf_size?)))
+                .collect();
+            run_lookups(inputs, |(hostname, buf_size)| async move {
+                dns_lookup_txt_edns_async(&hostname, buf_size).await.ok()
+            })
+        } else {
+            let resolver = &GLOBAL_DNS_STATE.resolver;
+            run_lookups(hostnames, |hostname| async move {
+                dns_lookup_txt_async(resolver, &hostname).await.ok()
+            })
+        };
+
+        write_varchar_list_results(output, input.len(), &results);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Integer),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+        ]
+    }
+}
+
+/// SPF policy lookup scalar function
+///
+/// Returns the SPF policy a domain publishes in its TXT records.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * VARCHAR - The whole SPF record, or NULL if the domain has no SPF record or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_spf('google.com');
+/// -- Returns: v=spf1 include:_spf.google.com ~all
+/// ```
+struct DnsLookupSpf;
+
+impl VScalar for DnsLookupSpf {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dns_lookup_spf_async(resolver, &domain).await.ok()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(record) => output_vector.insert(i, record.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// SPF include extraction scalar function
+///
+/// Returns the domains a domain's SPF policy delegates to via `include:` mechanisms.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * VARCHAR[] - The included domains in policy order (empty if the policy has no
+///   includes), or NULL if the domain has no SPF record or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_spf_includes('google.com');
+/// -- Returns: [_spf.google.com]
+/// ```
+struct DnsSpfIncludes;
+
+impl VScalar for DnsSpfIncludes {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dns_lookup_spf_async(resolver, &domain).await.ok().map(|record| spf_includes(&record))
+        });
+
+        write_varchar_list_results(output, input.len(), &results);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        )]
+    }
+}
+
+/// DMARC record lookup scalar function
+///
+/// Returns the DMARC policy record a domain publishes at `_dmarc.<domain>`.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain (e.g., "google.com"), without `_dmarc.`
+///
+/// # Returns
+/// * VARCHAR - The whole DMARC record, or NULL if the domain has no DMARC record or the
+///   lookup fails. Other TXT records at the name are ignored.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_dmarc('google.com');
+/// -- Returns: v=DMARC1; p=reject; rua=mailto:mailauth-reports@google.com
+/// ```
+struct DnsLookupDmarc;
+
+impl VScalar for DnsLookupDmarc {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dns_lookup_dmarc_async(resolver, &domain).await.ok()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(record) => output_vector.insert(i, record.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// DMARC policy scalar function
+///
+/// Returns the policy a domain requests for mail failing DMARC, the `p=` tag of its
+/// DMARC record.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain (e.g., "google.com"), without `_dmarc.`
+///
+/// # Returns
+/// * VARCHAR - The lowercased `p=` value ('none', 'quarantine' or 'reject'), or NULL if
+///   the domain has no DMARC record, the record has no `p=` tag, or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_dmarc_policy('google.com');
+/// -- Returns: reject
+/// ```
+struct DnsDmarcPolicy;
+
+impl VScalar for DnsDmarcPolicy {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dmarc_policy(&dns_lookup_dmarc_async(resolver, &domain).await.ok()?)
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(policy) => output_vector.insert(i, policy.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Forward DNS lookup scalar function with failure status
+///
+/// Companion of the single-argument `dns_lookup` that tells apart why a lookup
+/// returned no address, for data-quality diagnostics.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * STRUCT(address VARCHAR, error VARCHAR) - On success the first IPv4 address and a NULL
+///   error; on failure a NULL address and the classified failure (e.g. "NXDOMAIN",
+///   "no A record", "servfail", "timeout"). NULL for a NULL hostname.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_with_status('nonexistent-subdomain-12345.google.com');
+/// -- Returns: {'address': NULL, 'error': NXDOMAIN}
+/// ```
+struct DnsLookupWithStatus;
+
+impl VScalar for DnsLookupWithStatus {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            Some(dns_lookup_with_status_async(resolver, &hostname).await)
+        });
+
+        let mut struct_vector = output.struct_vector();
+        let mut address_vector = struct_vector.child(0, size);
+        let mut error_vector = struct_vector.child(1, size);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(Ok(address)) => {
+                    address_vector.insert(i, address.as_str());
+                    error_vector.set_null(i);
+                }
+                Some(Err(error)) => {
+                    address_vector.set_null(i);
+                    error_vector.insert(i, error.as_str());
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::struct_type(&[
+                ("address", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("error", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ]),
+        )]
+    }
+}
+
+/// Forward DNS lookup scalar function returning all IPv4 addresses with a status
+///
+/// Companion of the single-argument `dns_lookup_all` that tells failed lookups apart
+/// from names without addresses, to quantify failure modes across domain lists.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * STRUCT(addresses VARCHAR[], status VARCHAR) - `status` is 'ok', 'nxdomain',
+///   'no_records', 'timeout' or 'error'. With 'ok', `addresses` lists the IPv4 addresses
+///   (empty if all are excluded by the IP filter); otherwise it is NULL. NULL for a NULL
+///   hostname.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_all_with_status('nonexistent-subdomain-12345.google.com');
+/// -- Returns: {'addresses': NULL, 'status': nxdomain}
+/// ```
+struct DnsLookupAllWithStatus;
+
+impl VScalar for DnsLookupAllWithStatus {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            Some(dns_lookup_all_with_status_async(resolver, &hostname).await)
+        });
+
+        let mut struct_vector = output.struct_vector();
+        let mut addresses_vector = struct_vector.list_vector_child(0);
+        let status_vector = struct_vector.child(1, size);
+
+        let total_capacity: usize = results.iter().flatten().flatten().map(Vec::len).sum();
+        let address_child = addresses_vector.child(total_capacity);
+
+        let mut offset = 0;
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(Ok(addresses)) => {
+                    addresses_vector.set_entry(i, offset, addresses.len());
+                    for address in addresses {
+                        address_child.insert(offset, address.as_str());
+                        offset += 1;
+                    }
+                    status_vector.insert(i, "ok");
+                }
+                Some(Err(status)) => {
+                    addresses_vector.set_null(i);
+                    status_vector.insert(i, *status);
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+        addresses_vector.set_len(total_capacity);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::struct_type(&[
+                (
+                    "addresses",
+                    LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ),
+                ("status", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ]),
+        )]
+    }
+}
+
+/// Reverse DNS lookup scalar function (returns all hostnames)
+///
+/// Returns every PTR hostname of an address, for audits of multi-homed IPs that
+/// legitimately map to several names. IPv6 addresses, including compressed `::` forms,
+/// are queried under their full 32-nibble `ip6.arpa` name.
+///
+/// # Arguments
+/// * `ip_address` - A VARCHAR containing an IPv4 or IPv6 address (e.g., "8.8.8.8")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true keeps the trailing FQDN dot (default: false)
+///
+/// # Returns
+/// * VARCHAR[] - All resolved hostnames without trailing dots, in the resolver's order,
+///   or NULL if the address has no PTR records or the lookup fails. A malformed address
+///   raises an error.
+///
+/// # Example
+/// ```sql
+/// SELECT reverse_dns_lookup_all('8.8.8.8') as hostnames;
+/// -- Returns: [dns.google]
+///
+/// SELECT reverse_dns_lookup_all('2001:4860:4860::8888') as hostnames;
+/// -- Returns: [dns.google]
+/// ```
+struct ReverseDnsLookupAll;
+
+impl VScalar for ReverseDnsLookupAll {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let ip_addresses = read_name_column(input, 0);
+
+        // A malformed address is a mistake in the query, not a lookup failure: fail the
+        // whole query instead of returning NULL
+        for ip_address in ip_addresses.iter().flatten() {
+            validate_ip(ip_address)?;
+        }
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(ip_addresses, |ip_address| async move {
+            reverse_dns_lookup_all_async(resolver, &ip_address).await.ok()
+        });
+
+        let keep_trailing_dot = read_keep_trailing_dot(input, 1);
+        let results: Vec<Option<Vec<String>>> = results
+            .into_iter()
+            .zip(keep_trailing_dot)
+            .map(|(names, keep)| names.map(|names| names.iter().map(|name| format_domain_name(name, keep)).collect()))
+            .collect();
+        write_varchar_list_results(output, input.len(), &results);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+        ]
+    }
+}
+
+/// SRV lookup scalar function
+///
+/// Returns the SRV records of a service name with all fields broken out, for service
+/// discovery queries that sort by priority and weight in SQL.
+///
+/// # Arguments
+/// * `name` - A VARCHAR containing the service name (e.g., "_sip._tcp.example.com")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true keeps the trailing FQDN dot (default: false)
+///
+/// # Returns
+/// * STRUCT(priority USMALLINT, weight USMALLINT, port USMALLINT, target VARCHAR)[] - One
+///   element per SRV record sorted by priority ascending, or NULL if the name has no SRV
+///   records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_srv('_xmpp-server._tcp.jabber.org');
+/// -- Returns: [{'priority': 31, 'weight': 30, 'port': 5269, 'target': hermes2.jabber.org}]
+/// ```
+struct DnsLookupSrv;
+
+impl VScalar for DnsLookupSrv {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let names = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(names, |name| async move {
+            dns_lookup_srv_async(resolver, &name).await.ok()
+        });
+        let results: Vec<_> = results
+            .into_iter()
+            .zip(read_keep_trailing_dot(input, 1))
+            .map(|(records, keep)| {
+                records.map(|records| {
+                    records
+                        .into_iter()
+                        .map(|record| SrvRecord { target: format_domain_name(&record.target, keep), ..record })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        write_struct_list_results(output, input.len(), &results, 4, |fields, offset, record| {
+            fields[0].as_mut_slice::<u16>()[offset] = record.priority;
+            fields[1].as_mut_slice::<u16>()[offset] = record.weight;
+            fields[2].as_mut_slice::<u16>()[offset] = record.port;
+            fields[3].insert(offset, record.target.as_str());
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        let returns = || {
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("priority", LogicalTypeHandle::from(LogicalTypeId::USmallint)),
+                ("weight", LogicalTypeHandle::from(LogicalTypeId::USmallint)),
+                ("port", LogicalTypeHandle::from(LogicalTypeId::USmallint)),
+                ("target", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ]))
+        };
+        vec![
+            ScalarFunctionSignature::exact(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)], returns()),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                returns(),
+            ),
+        ]
+    }
+}
+
+/// DNS-over-HTTPS lookup scalar function
+///
+/// Resolves a hostname through a DoH endpoint instead of the session resolver, for
+/// privacy or on networks that block plain DNS. Queries are sent over HTTP/2; HTTP/3
+/// requires building the extension with the `doh3` Cargo feature.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+/// * `doh_url` - A VARCHAR containing the DoH endpoint (e.g., "https://cloudflare-dns.com/dns-query")
+/// * `http_version` - Optional VARCHAR, 'h2' (default) or 'h3'
+///
+/// # Returns
+/// * VARCHAR - The first IPv4 address, or NULL if the lookup fails or any argument is NULL
+///
+/// # Errors
+/// Raises an error for a malformed URL or an unknown HTTP version, or for 'h3' when the
+/// `doh3` feature is not enabled
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_doh('google.com', 'https://cloudflare-dns.com/dns-query');
+/// -- Returns: 142.250.181.206
+/// ```
+struct DnsLookupDoh;
+
+impl VScalar for DnsLookupDoh {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let versions: Vec<Option<String>> = if input.num_columns() > 2 {
+            read_varchar_column(input, 2)
+        } else {
+            vec![Some("h2".to_string()); input.len()]
+        };
+        let resolvers = read_varchar_column(input, 1)
+            .into_iter()
+            .zip(versions)
+            .map(|(url, version)| match (url, version) {
+                (Some(url), Some(version)) => {
+                    GLOBAL_DNS_STATE.doh_resolver(&url, parse_doh_version(&version)?).map(Some)
+                }
+                _ => Ok(None),
+            })
+            .collect::<std::result::Result<Vec<_>, Box<dyn Error>>>()?;
+
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(resolvers)
+            .map(|(hostname, resolver)| Some((hostname?, resolver?)))
+            .collect();
+        let results = run_lookups(inputs, |(hostname, resolver)| async move {
+            dns_lookup_async(&resolver, &hostname, AddressFamily::Ipv4).await.ok()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(address) => output_vector.insert(i, address.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+        ]
+    }
+}
+
+/// DNS-over-TLS lookup scalar function
+///
+/// Resolves a hostname through a DoT server on port 853. The server certificate must be
+/// valid for `tls_name`; a mismatch fails the lookup. Requires building the extension
+/// with the `dot` Cargo feature.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+/// * `server_ip` - A VARCHAR containing the server address, optionally with port (e.g., "1.1.1.1")
+/// * `tls_name` - A VARCHAR containing the name the certificate is validated against
+///   (e.g., "cloudflare-dns.com")
+///
+/// # Returns
+/// * VARCHAR - The first IPv4 address, or NULL if the lookup, the connection or the
+///   certificate validation fails, or any argument is NULL
+///
+/// # Errors
+/// Raises an error for an invalid server address or empty TLS name, or when the `dot`
+/// feature is not enabled
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_dot('google.com', '1.1.1.1', 'cloudflare-dns.com');
+/// -- Returns: 142.250.181.206
+/// ```
+struct DnsLookupDot;
+
+impl VScalar for DnsLookupDot {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let resolvers = read_varchar_column(input, 1)
+            .into_iter()
+            .zip(read_varchar_column(input, 2))
+            .map(|(server, tls_name)| match (server, tls_name) {
+                (Some(server), Some(tls_name)) => GLOBAL_DNS_STATE.dot_resolver(&server, &tls_name).map(Some),
+                _ => Ok(None),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(resolvers)
+            .map(|(hostname, resolver)| Some((hostname?, resolver?)))
+            .collect();
+        let results = run_lookups(inputs, |(hostname, resolver)| async move {
+            dns_lookup_async(&resolver, &hostname, AddressFamily::Ipv4).await.ok()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(address) => output_vector.insert(i, address.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// NS lookup scalar function
+///
+/// Returns the authoritative name servers of a domain, e.g. to verify delegations or
+/// find domains still pointing at a decommissioned name server.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain to resolve (e.g., "google.com")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true keeps the trailing FQDN dot (default: false)
+///
+/// # Returns
+/// * VARCHAR[] - The name server hostnames without trailing dots, in resolver order, or
+///   NULL if the name has no NS records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_ns('google.com');
+/// -- Returns: [ns1.google.com, ns2.google.com, ns3.google.com, ns4.google.com]
+/// ```
+struct DnsLookupNs;
+
+impl VScalar for DnsLookupNs {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dns_lookup_ns_async(resolver, &domain).await.ok()
+        });
+
+        let keep_trailing_dot = read_keep_trailing_dot(input, 1);
+        let results: Vec<Option<Vec<String>>> = results
+            .into_iter()
+            .zip(keep_trailing_dot)
+            .map(|(names, keep)| names.map(|names| names.iter().map(|name| format_domain_name(name, keep)).collect()))
+            .collect();
+        write_varchar_list_results(output, input.len(), &results);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+        ]
+    }
+}
+
+/// SOA lookup scalar function
+///
+/// Returns the SOA record of a zone with its fields broken out, e.g. to monitor zone
+/// serial numbers across domains.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the zone apex (e.g., "google.com")
+/// * `keep_trailing_dot` - Optional BOOLEAN; true keeps the trailing FQDN dot of `mname`
+///   and `rname` (default: false)
+///
+/// # Returns
+/// * STRUCT(mname VARCHAR, rname VARCHAR, serial UINTEGER, refresh INTEGER, retry INTEGER,
+///   expire INTEGER, minimum UINTEGER) - The SOA fields, or NULL if no SOA record exists
+///   at the name or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT (dns_lookup_soa('google.com')).serial;
+/// -- Returns: 123456789
+/// ```
+struct DnsLookupSoa;
+
+impl VScalar for DnsLookupSoa {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dns_lookup_soa_async(resolver, &domain).await.ok()
+        });
+        let keep_trailing_dot = read_keep_trailing_dot(input, 1);
+
+        let mut struct_vector = output.struct_vector();
+        let mname_vector = struct_vector.child(0, size);
+        let rname_vector = struct_vector.child(1, size);
+        let mut serial_vector = struct_vector.child(2, size);
+        let mut refresh_vector = struct_vector.child(3, size);
+        let mut retry_vector = struct_vector.child(4, size);
+        let mut expire_vector = struct_vector.child(5, size);
+        let mut minimum_vector = struct_vector.child(6, size);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(soa) => {
+                    mname_vector.insert(i, format_domain_name(&soa.mname, keep_trailing_dot[i]).as_str());
+                    rname_vector.insert(i, format_domain_name(&soa.rname, keep_trailing_dot[i]).as_str());
+                    serial_vector.as_mut_slice::<u32>()[i] = soa.serial;
+                    refresh_vector.as_mut_slice::<i32>()[i] = soa.refresh;
+                    retry_vector.as_mut_slice::<i32>()[i] = soa.retry;
+                    expire_vector.as_mut_slice::<i32>()[i] = soa.expire;
+                    minimum_vector.as_mut_slice::<u32>()[i] = soa.minimum;
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        let returns = || {
+            LogicalTypeHandle::struct_type(&[
+                ("mname", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("rname", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("serial", LogicalTypeHandle::from(LogicalTypeId::UInteger)),
+                ("refresh", LogicalTypeHandle::from(LogicalTypeId::Integer)),
+                ("retry", LogicalTypeHandle::from(LogicalTypeId::Integer)),
+                ("expire", LogicalTypeHandle::from(LogicalTypeId::Integer)),
+                ("minimum", LogicalTypeHandle::from(LogicalTypeId::UInteger)),
+            ])
+        };
+        vec![
+            ScalarFunctionSignature::exact(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)], returns()),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Boolean),
+                ],
+                returns(),
+            ),
+        ]
+    }
+}
+
+/// Domain administrator scalar function
+///
+/// Returns the email address of the party responsible for a zone, decoded from the
+/// `rname` field of its SOA record, as a quasi-ownership signal.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the zone apex (e.g., "google.com")
+///
+/// # Returns
+/// * VARCHAR - The email address (e.g., "dns-admin@google.com"), or NULL if no SOA
+///   record exists at the name, its `rname` is not an address, or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_domain_admin('google.com');
+/// -- Returns: dns-admin@google.com
+/// ```
+struct DnsDomainAdmin;
+
+impl VScalar for DnsDomainAdmin {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            soa_rname_email(&dns_lookup_soa_async(resolver, &domain).await.ok()?.rname)
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(email) => output_vector.insert(i, email.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// CAA lookup scalar function
+///
+/// Returns the CAA records of a domain, for auditing which certificate authorities may
+/// issue certificates for it.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * STRUCT(flags UTINYINT, tag VARCHAR, value VARCHAR)[] - One element per CAA record
+///   (e.g. tags `issue`, `issuewild`, `iodef`), or NULL if the domain has no CAA records
+///   or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_caa('google.com');
+/// -- Returns: [{'flags': 0, 'tag': issue, 'value': pki.goog}]
+/// ```
+struct DnsLookupCaa;
+
+impl VScalar for DnsLookupCaa {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dns_lookup_caa_async(resolver, &domain).await.ok()
+        });
+
+        write_struct_list_results(output, input.len(), &results, 3, |fields, offset, (flags, tag, value)| {
+            fields[0].as_mut_slice::<u8>()[offset] = *flags;
+            fields[1].insert(offset, tag.as_str());
+            fields[2].insert(offset, value.as_str());
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("flags", LogicalTypeHandle::from(LogicalTypeId::UTinyint)),
+                ("tag", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("value", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ])),
+        )]
+    }
+}
+
+/// TLSA lookup scalar function
+///
+/// Returns the TLSA records of a service, for auditing DANE (RFC 6698) deployments.
+///
+/// # Arguments
+/// * `name` - A VARCHAR containing the TLSA owner name, port and protocol prefixed
+///   (e.g., "_443._tcp.example.com")
+///
+/// # Returns
+/// * STRUCT(usage UTINYINT, selector UTINYINT, matching_type UTINYINT, certificate_data VARCHAR)[] -
+///   One element per TLSA record, the certificate association data as lowercase hex,
+///   or NULL if the name has no TLSA records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_tlsa('_443._tcp.www.huque.com');
+/// -- Returns: [{'usage': 3, 'selector': 1, 'matching_type': 1, 'certificate_data': 5a1d...}]
+/// ```
+struct DnsLookupTlsa;
+
+impl VScalar for DnsLookupTlsa {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let names = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(names, |name| async move {
+            dns_lookup_tlsa_async(resolver, &name).await.ok()
+        });
+
+        write_struct_list_results(output, input.len(), &results, 4, |fields, offset, record| {
+            fields[0].as_mut_slice::<u8>()[offset] = record.usage;
+            fields[1].as_mut_slice::<u8>()[offset] = record.selector;
+            fields[2].as_mut_slice::<u8>()[offset] = record.matching_type;
+            fields[3].insert(offset, record.certificate_data.as_str());
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("usage", LogicalTypeHandle::from(LogicalTypeId::UTinyint)),
+                ("selector", LogicalTypeHandle::from(LogicalTypeId::UTinyint)),
+                ("matching_type", LogicalTypeHandle::from(LogicalTypeId::UTinyint)),
+                ("certificate_data", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ])),
+        )]
+    }
+}
+
+/// DNS blocklist check scalar function
+///
+/// Checks whether an IPv4 address is listed on a DNS-based blocklist (DNSBL).
+///
+/// # Arguments
+/// * `ip_address` - A VARCHAR containing the IPv4 address to check (e.g., "127.0.0.2")
+/// * `blocklist_zone` - A VARCHAR containing the blocklist zone (e.g., "zen.spamhaus.org")
+///
+/// # Returns
+/// * BOOLEAN - TRUE if listed, FALSE if not listed, or NULL if the address is not a
+///   valid IPv4 address or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_check_blocklist('127.0.0.2', 'zen.spamhaus.org');
+/// -- Returns: true
+/// ```
+struct DnsCheckBlocklist;
+
+impl VScalar for DnsCheckBlocklist {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let ips = read_name_column(input, 0);
+        let zones = read_name_column(input, 1);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = ips
+            .into_iter()
+            .zip(zones)
+            .map(|(ip, zone)| Some((ip?, zone?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(ip, zone)| async move {
+            dns_check_blocklist_async(resolver, &ip, &zone).await.ok()
+        });
+
+        write_boolean_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ],
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        )]
+    }
+}
+
+/// Forward-confirmed reverse DNS scalar function
+///
+/// Checks that an address's PTR hostname resolves back to the address, as mail servers
+/// do to validate connecting clients.
+///
+/// # Arguments
+/// * `ip_address` - A VARCHAR containing the IPv4 or IPv6 address to check (e.g., "8.8.8.8")
+///
+/// # Returns
+/// * BOOLEAN - TRUE if a PTR hostname resolves back to the address, FALSE if there is no
+///   PTR record or none of its hostnames does, or NULL if the address is invalid or a
+///   lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_fcrdns('8.8.8.8');
+/// -- Returns: true (8.8.8.8 -> dns.google -> 8.8.8.8)
+/// ```
+struct DnsFcrdns;
+
+impl VScalar for DnsFcrdns {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let ips = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(ips, |ip| async move {
+            dns_fcrdns_async(resolver, &ip).await.ok()
+        });
+
+        write_boolean_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        )]
+    }
+}
+
+/// IP-to-ASN lookup scalar function
+///
+/// Looks up the origin AS, announced prefix and country of an address through a
+/// DNS-based IP-to-ASN service, Team Cymru's by default.
+///
+/// # Arguments
+/// * `ip_address` - A VARCHAR containing the IPv4 or IPv6 address (e.g., "8.8.8.8")
+/// * `zone` - Optional VARCHAR with the query zone of an alternate provider; defaults to
+///   'origin.asn.cymru.com' for IPv4 and 'origin6.asn.cymru.com' for IPv6 addresses
+///
+/// # Returns
+/// * STRUCT(asn UINTEGER, prefix VARCHAR, country VARCHAR) - The first AS announcing
+///   the prefix, the prefix in CIDR notation and the ISO country code, or NULL if the
+///   address is invalid, the answer is missing or malformed, or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_asn('8.8.8.8');
+/// -- Returns: {'asn': 15169, 'prefix': 8.8.8.0/24, 'country': US}
+/// ```
+struct DnsLookupAsn;
+
+impl VScalar for DnsLookupAsn {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let ips = read_name_column(input, 0);
+
+        // With a zone argument, a NULL zone makes the row NULL like a NULL address
+        let inputs: Vec<Option<(String, Option<String>)>> = if input.num_columns() > 1 {
+            ips.into_iter()
+                .zip(read_name_column(input, 1))
+                .map(|(ip, zone)| Some((ip?, Some(zone?))))
+                .collect()
+        } else {
+            ips.into_iter().map(|ip| Some((ip?, None))).collect()
+        };
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+
+        let results = run_lookups(inputs, |(ip, zone)| async move {
+            dns_lookup_asn_async(resolver, &ip, zone.as_deref()).await.ok()
+        });
+
+        let mut struct_vector = output.struct_vector();
+        let mut asn_vector = struct_vector.child(0, size);
+        let prefix_vector = struct_vector.child(1, size);
+        let mut country_vector = struct_vector.child(2, size);
+
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(info) => {
+                    asn_vector.as_mut_slice::<u32>()[i] = info.asn;
+                    prefix_vector.insert(i, info.prefix.as_str());
+                    match &info.country {
+                        Some(country) => country_vector.insert(i, country.as_str()),
+                        None => country_vector.set_null(i),
+                    }
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        let returns = || {
+            LogicalTypeHandle::struct_type(&[
+                ("asn", LogicalTypeHandle::from(LogicalTypeId::UInteger)),
+                ("prefix", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("country", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ])
+        };
+        vec![
+            ScalarFunctionSignature::exact(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)], returns()),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                returns(),
+            ),
+        ]
+    }
+}
+
+/// DNS wildcard detection scalar function
+///
+/// Checks whether a hostname resolves only because its parent domain has a DNS
+/// wildcard, e.g. when auditing which subdomains really exist.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to check (e.g., "anything.example.com")
+///
+/// # Returns
+/// * BOOLEAN - TRUE if the hostname answers with the same IPv4 addresses as a random
+///   nonexistent sibling, FALSE if it does not resolve or resolves on its own, or NULL
+///   if the hostname has no parent domain or a lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_is_wildcard('does-not-exist.github.io');
+/// -- Returns: true
+/// ```
+struct DnsIsWildcard;
+
+impl VScalar for DnsIsWildcard {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_is_wildcard_async(resolver, &hostname).await.ok()
+        });
+
+        write_boolean_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        )]
+    }
+}
+
+/// Name existence scalar function
+///
+/// Checks whether a hostname resolves at all, distinguishing names without records
+/// from failed lookups.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * BOOLEAN - TRUE if any A or AAAA record exists, FALSE on NXDOMAIN or no address
+///   records, or NULL if the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_resolves('google.com');
+/// -- Returns: true
+/// ```
+struct DnsResolves;
+
+impl VScalar for DnsResolves {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_resolves_async(resolver, &hostname).await.ok()
+        });
+
+        write_boolean_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        )]
+    }
+}
+
+/// Combined IPv4 and IPv6 lookup scalar function
+///
+/// Resolves a hostname's A and AAAA records in one call, so the two families do not
+/// have to be looked up separately and unioned.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * STRUCT(family VARCHAR, address VARCHAR)[] - One element per address, `family` is
+///   'A' or 'AAAA'; IPv4 addresses come first, each family in resolver order. NULL if
+///   the name has neither A nor AAAA records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_addrs('dns.google');
+/// -- Returns: [{'family': A, 'address': 8.8.8.8}, ..., {'family': AAAA, 'address': 2001:4860:4860::8888}, ...]
+/// ```
+struct DnsLookupAddrs;
+
+impl VScalar for DnsLookupAddrs {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = GLOBAL_DNS_STATE.dual_stack_resolver()?;
+        let resolver = resolver.as_ref();
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_addrs_async(resolver, &hostname).await.ok()
+        });
+
+        write_struct_list_results(output, input.len(), &results, 2, |fields, offset, (family, address)| {
+            fields[0].insert(offset, *family);
+            fields[1].insert(offset, address.as_str());
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("family", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("address", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ])),
+        )]
+    }
+}
+
+/// Per-family forward DNS lookup scalar function
+///
+/// Resolves a hostname's A and AAAA records in one call and groups the addresses by
+/// record type, so a family's addresses can be picked by key instead of filtering a list.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * MAP(VARCHAR, VARCHAR[]) - Addresses per record type, keyed 'A' and 'AAAA' in resolver
+///   order; families without records are omitted. NULL if the name has neither A nor AAAA
+///   records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_map('dns.google');
+/// -- Returns: {A=[8.8.8.8, 8.8.4.4], AAAA=[2001:4860:4860::8888, 2001:4860:4860::8844]}
+/// ```
+struct DnsLookupMap;
+
+impl VScalar for DnsLookupMap {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = GLOBAL_DNS_STATE.dual_stack_resolver()?;
+        let resolver = resolver.as_ref();
+        let results = run_lookups(hostnames, |hostname| async move {
+            let addresses = dns_lookup_addrs_async(resolver, &hostname).await.ok()?;
+            let mut families: Vec<(&'static str, Vec<String>)> = Vec::new();
+            for (family, address) in addresses {
+                match families.last_mut() {
+                    Some((last, group)) if *last == family => group.push(address),
+                    _ => families.push((family, vec![address])),
+                }
+            }
+            Some(families)
+        });
+
+        // A MAP is a list of (key, value) structs, each value here a list of addresses
+        let mut output_vector = output.list_vector();
+        let total_entries: usize = results.iter().flatten().map(Vec::len).sum();
+        let total_addresses: usize = results
+            .iter()
+            .flatten()
+            .flat_map(|families| families.iter().map(|(_, group)| group.len()))
+            .sum();
+        let entries = output_vector.struct_child(total_entries);
+        let keys = entries.child(0, total_entries);
+        let mut values = entries.list_vector_child(1);
+        let address_vector = values.child(total_addresses);
+
+        let mut entry_offset = 0;
+        let mut address_offset = 0;
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(families) => {
+                    output_vector.set_entry(i, entry_offset, families.len());
+                    for (family, group) in families {
+                        keys.insert(entry_offset, *family);
+                        values.set_entry(entry_offset, address_offset, group.len());
+                        for address in group {
+                            address_vector.insert(address_offset, address.as_str());
+                            address_offset += 1;
+                        }
+                        entry_offset += 1;
+                    }
+                }
+                None => output_vector.set_null(i),
+            }
+        }
+        values.set_len(total_addresses);
+        output_vector.set_len(total_entries);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::map(
+                &LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                &LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ),
+        )]
+    }
+}
+
+/// Detailed forward DNS lookup scalar function
+///
+/// Resolves a hostname's A and AAAA records in one call and returns every address with
+/// its family and TTL.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+///
+/// # Returns
+/// * STRUCT(address VARCHAR, family VARCHAR, ttl UINTEGER)[] - One element per address in
+///   answer order; `family` is 'A' or 'AAAA' and `ttl` the remaining TTL in seconds.
+///   NULL if the name has neither A nor AAAA records or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_detailed('dns.google');
+/// -- Returns: [{'address': 8.8.8.8, 'family': A, 'ttl': 900}, ..., {'address': 2001:4860:4860::8888, 'family': AAAA, 'ttl': 900}, ...]
+/// ```
+struct DnsLookupDetailed;
+
+impl VScalar for DnsLookupDetailed {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = GLOBAL_DNS_STATE.dual_stack_resolver()?;
+        let resolver = resolver.as_ref();
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_detailed_async(resolver, &hostname).await.ok()
+        });
+
+        write_struct_list_results(output, input.len(), &results, 3, |fields, offset, detail| {
+            fields[0].insert(offset, detail.address.as_str());
+            fields[1].insert(offset, detail.family);
+            fields[2].as_mut_slice::<u32>()[offset] = detail.ttl;
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("address", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("family", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("ttl", LogicalTypeHandle::from(LogicalTypeId::UInteger)),
+            ])),
+        )]
+    }
+}
+
+/// Resolved FQDN scalar function
+///
+/// Returns the fully qualified name that answered a forward lookup, revealing which
+/// search domain (if any) the resolver appended to a short name.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "intranet")
+///
+/// # Returns
+/// * VARCHAR - The name the resolver matched, without trailing dot
+///   (e.g., "intranet.corp.example.com"), or NULL if the name does not resolve
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_resolved_fqdn('intranet');
+/// -- Returns: intranet.corp.example.com
+/// ```
+struct DnsLookupResolvedFqdn;
+
+impl VScalar for DnsLookupResolvedFqdn {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_resolved_fqdn_async(resolver, &hostname).await.ok()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(name) => output_vector.insert(i, name.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// DNSSEC-aware forward DNS lookup scalar function
+///
+/// Resolves a hostname to its IPv4 addresses and reports whether the answer was
+/// DNSSEC-validated. Validation is done by the extension, from the root trust anchor down.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "cloudflare.com")
+///
+/// # Returns
+/// * STRUCT(address VARCHAR, authenticated BOOLEAN)[] - One element per IPv4 address;
+///   `authenticated` is true if the answer was proven secure, false for unsigned zones.
+///   NULL if the lookup fails or the answer is bogus
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_secure('cloudflare.com');
+/// -- Returns: [{'address': 104.16.132.229, 'authenticated': true}, ...]
+/// ```
+struct DnsLookupSecure;
+
+impl VScalar for DnsLookupSecure {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = GLOBAL_DNS_STATE.validating_resolver()?;
+        let resolver = resolver.as_ref();
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_secure_async(resolver, &hostname).await.ok()
+        });
+
+        let results: Vec<_> = results
+            .into_iter()
+            .map(|result| {
+                let (addresses, authenticated) = result?;
+                Some(addresses.into_iter().map(|address| (address, authenticated)).collect::<Vec<_>>())
+            })
+            .collect();
+
+        write_struct_list_results(output, input.len(), &results, 2, |fields, offset, (address, authenticated)| {
+            fields[0].insert(offset, address.as_str());
+            fields[1].as_mut_slice::<bool>()[offset] = *authenticated;
+        });
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("address", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("authenticated", LogicalTypeHandle::from(LogicalTypeId::Boolean)),
+            ])),
+        )]
+    }
+}
+
+/// Forward DNS lookup debugging scalar function
+///
+/// Shows which name the resolver actually queried and which name the addresses belong
+/// to, to diagnose search-domain expansion and CNAME chains.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "www.github.com")
+///
+/// # Returns
+/// * STRUCT(queried_name VARCHAR, canonical_name VARCHAR, addresses VARCHAR[]):
+///   - `queried_name`: The name sent to the name servers, after search-domain expansion
+///   - `canonical_name`: The owner name of the address records, NULL without addresses
+///   - `addresses`: The IPv4 and IPv6 addresses, empty if the name has none
+///   - NULL if the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_debug('www.github.com');
+/// -- Returns: {'queried_name': www.github.com, 'canonical_name': github.com, 'addresses': [140.82.121.4]}
+/// ```
+struct DnsLookupDebug;
+
+impl VScalar for DnsLookupDebug {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_lookup_debug_async(resolver, &hostname).await.ok()
+        });
+
+        let mut struct_vector = output.struct_vector();
+        let queried_name_vector = struct_vector.child(0, size);
+        let mut canonical_name_vector = struct_vector.child(1, size);
+        let mut addresses_vector = struct_vector.list_vector_child(2);
+
+        let total_capacity: usize = results.iter().flatten().map(|debug| debug.addresses.len()).sum();
+        let address_child = addresses_vector.child(total_capacity);
+
+        let mut offset = 0;
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(debug) => {
+                    queried_name_vector.insert(i, debug.queried_name.as_str());
+                    match &debug.canonical_name {
+                        Some(canonical_name) => canonical_name_vector.insert(i, canonical_name.as_str()),
+                        None => canonical_name_vector.set_null(i),
+                    }
+                    addresses_vector.set_entry(i, offset, debug.addresses.len());
+                    for address in &debug.addresses {
+                        address_child.insert(offset, address.as_str());
+                        offset += 1;
+                    }
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+        addresses_vector.set_len(total_capacity);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::struct_type(&[
+                ("queried_name", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("canonical_name", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                (
+                    "addresses",
+                    LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ),
+            ]),
+        )]
+    }
+}
+
+/// TXT token verification scalar function
+///
+/// Checks whether a hostname's TXT records contain a given token, e.g. a
+/// domain-ownership verification string.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to query (e.g., "google.com")
+/// * `token` - A VARCHAR containing the token to look for
+/// * `mode` - Optional VARCHAR: 'exact' (default) matches whole records, 'substring' matches anywhere in a record
+///
+/// # Returns
+/// * BOOLEAN - true if a TXT record matches, false if none match or no TXT records exist, NULL on lookup error
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_txt_contains('google.com', 'v=spf1 include:_spf.google.com ~all');
+/// -- Returns: true
+///
+/// SELECT dns_txt_contains('google.com', 'google-site-verification=', 'substring');
+/// -- Returns: true
+/// ```
+struct DnsTxtContains;
+
+impl VScalar for DnsTxtContains {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let tokens = read_varchar_column(input, 1);
+        let modes = if input.num_columns() > 2 {
+            read_varchar_column(input, 2)
+                .into_iter()
+                .map(|mode| mode.map(|m| parse_txt_match_mode(&m)).transpose())
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            vec![Some(TxtMatchMode::Exact); hostnames.len()]
+        };
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(tokens)
+            .zip(modes)
+            .map(|((hostname, token), mode)| Some((hostname?, token?, mode?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname, token, mode)| async move {
+            dns_txt_contains_async(resolver, &hostname, &token, mode).await.ok()
+        });
+
+        write_boolean_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Boolean),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Boolean),
+            ),
+        ]
+    }
+}
+
+/// Longest TXT record scalar function
+///
+/// Returns the single longest TXT record of a hostname. DKIM selectors usually
+/// publish the public key as their longest record, so this extracts it without
+/// iterating a list in SQL.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to query (e.g., "selector1._domainkey.example.com")
+///
+/// # Returns
+/// * VARCHAR - The longest TXT record (multi-string records are concatenated), or NULL
+///   if no TXT records exist or the lookup fails
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_txt_longest('google._domainkey.google.com');
+/// -- Returns: v=DKIM1; k=rsa; p=MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEA...
+/// ```
+struct DnsTxtLongest;
+
+impl VScalar for DnsTxtLongest {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_txt_longest_async(resolver, &hostname).await.ok().flatten()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(txt) => output_vector.insert(i, txt.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// TXT record diff scalar function
+///
+/// Compares the TXT records of two hostnames, e.g. to track SPF or DKIM policy drift
+/// between environments. Records are compared after whitespace normalization.
+///
+/// # Arguments
+/// * `hostname_a` - A VARCHAR containing the baseline hostname (e.g., "example.com")
+/// * `hostname_b` - A VARCHAR containing the hostname to compare (e.g., "staging.example.com")
+///
+/// # Returns
+/// * STRUCT(added VARCHAR[], removed VARCHAR[]) - Records only present on `hostname_b` (added)
+///   and only present on `hostname_a` (removed), each sorted. A hostname without TXT records
+///   counts as an empty set. Returns NULL if either lookup fails.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_txt_diff('google.com', 'google.com');
+/// -- Returns: {'added': [], 'removed': []}
+///
+/// SELECT dns_txt_diff('example.com', 'staging.example.com').added;
+/// ```
+struct DnsTxtDiff;
+
+impl VScalar for DnsTxtDiff {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames_a = read_name_column(input, 0);
+        let hostnames_b = read_name_column(input, 1);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames_a
+            .into_iter()
+            .zip(hostnames_b)
+            .map(|(hostname_a, hostname_b)| Some((hostname_a?, hostname_b?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname_a, hostname_b)| async move {
+            dns_txt_diff_async(resolver, &hostname_a, &hostname_b).await.ok()
+        });
+
+        let mut struct_vector = output.struct_vector();
+        let mut added_vector = struct_vector.list_vector_child(0);
+        let mut removed_vector = struct_vector.list_vector_child(1);
+
+        let added_capacity: usize = results.iter().flatten().map(|diff| diff.added.len()).sum();
+        let removed_capacity: usize = results.iter().flatten().map(|diff| diff.removed.len()).sum();
+        let added_child = added_vector.child(added_capacity);
+        let removed_child = removed_vector.child(removed_capacity);
+
+        let (mut added_offset, mut removed_offset) = (0, 0);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(diff) => {
+                    added_vector.set_entry(i, added_offset, diff.added.len());
+                    for record in &diff.added {
+                        added_child.insert(added_offset, record.as_str());
+                        added_offset += 1;
+                    }
+                    removed_vector.set_entry(i, removed_offset, diff.removed.len());
+                    for record in &diff.removed {
+                        removed_child.insert(removed_offset, record.as_str());
+                        removed_offset += 1;
+                    }
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+        added_vector.set_len(added_capacity);
+        removed_vector.set_len(removed_capacity);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ],
+            LogicalTypeHandle::struct_type(&[
+                ("added", LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))),
+                ("removed", LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))),
+            ]),
+        )]
+    }
+}
+
+/// DNS record count scalar function
+///
+/// Profiles a domain's DNS footprint by counting the records of each common record type.
+/// The types A, AAAA, CAA, CNAME, MX, NS, SOA and TXT are queried concurrently.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to profile (e.g., "google.com")
+///
+/// # Returns
+/// * MAP(VARCHAR, INTEGER) - Record count per record type; types without records are omitted.
+///   Returns NULL on lookup error.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_record_counts('google.com');
+/// -- Returns: {A=1, AAAA=1, CAA=1, MX=1, NS=4, SOA=1, TXT=12}
+/// ```
+struct DnsRecordCounts;
+
+impl VScalar for DnsRecordCounts {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let mut output_vector = output.list_vector();
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_record_counts_async(resolver, &hostname).await.ok()
+        });
+
+        // A MAP is a list of (key, value) structs
+        let total_capacity: usize = results.iter().map(|r| r.as_ref().map_or(0, |v| v.len())).sum();
+        let entries = output_vector.struct_child(total_capacity);
+        let keys = entries.child(0, total_capacity);
+        let mut values = entries.child(1, total_capacity);
+
+        let mut offset = 0;
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(counts) => {
+                    output_vector.set_entry(i, offset, counts.len());
+                    for (record_type, count) in counts {
+                        keys.insert(offset, record_type.as_str());
+                        values.as_mut_slice::<i32>()[offset] = *count;
+                        offset += 1;
+                    }
+                }
+                None => output_vector.set_null(i),
+            }
+        }
+        output_vector.set_len(total_capacity);
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::map(
+                &LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                &LogicalTypeHandle::from(LogicalTypeId::Integer),
+            ),
+        )]
+    }
+}
+
+/// DNS fingerprint scalar function
+///
+/// Summarizes a domain's DNS state as a compact hash for change detection: store the
+/// fingerprint, re-run later and compare. The types A, AAAA, CAA, CNAME, MX, NS, SOA
+/// and TXT are queried concurrently and canonicalized (sorted, TTLs excluded) before hashing.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to fingerprint (e.g., "google.com")
+///
+/// # Returns
+/// * VARCHAR - A 64-bit FNV-1a hash as 16 hex digits, stable across runs and versions.
+///   A name without any records hashes to a fixed value. Returns NULL on lookup error.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_fingerprint('google.com');
+/// -- Returns: 3f9a0c51d2e87b64
+/// ```
+///
+/// # Note
+/// The SOA serial is part of the fingerprint, so any zone update changes it. Names whose
+/// answers rotate (e.g. load-balanced A records) may change between runs.
+struct DnsFingerprint;
+
+impl VScalar for DnsFingerprint {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(hostnames, |hostname| async move {
+            dns_fingerprint_async(resolver, &hostname).await.ok()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(fingerprint) => output_vector.insert(i, fingerprint.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Apex CNAME misconfiguration scalar function
+///
+/// Detects the invalid but common configuration of a CNAME record at a zone apex
+/// alongside its SOA/NS records, which breaks mail and other records of the zone.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the domain (zone apex) to check (e.g., "example.com")
+///
+/// # Returns
+/// * BOOLEAN - true if a CNAME exists next to the apex SOA/NS records, false when
+///   correctly configured, NULL on lookup error
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_apex_cname_misconfig('google.com');
+/// -- Returns: false
+/// ```
+struct DnsApexCnameMisconfig;
+
+impl VScalar for DnsApexCnameMisconfig {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let domains = read_name_column(input, 0);
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let results = run_lookups(domains, |domain| async move {
+            dns_apex_cname_misconfig_async(resolver, &domain).await.ok()
+        });
+
+        write_boolean_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Boolean),
+        )]
+    }
+}
+
+/// Happy Eyeballs connection scalar function
+///
+/// Resolves both A and AAAA records of a hostname and races TCP connections to them
+/// per RFC 8305 (IPv6 first, alternating families, 250 ms between attempts), modelling
+/// how browsers pick an endpoint.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to connect to (e.g., "google.com")
+/// * `port` - An INTEGER TCP port (1-65535)
+///
+/// # Returns
+/// * STRUCT(family VARCHAR, address VARCHAR) - The family ('A' or 'AAAA') and address
+///   that connected first, or NULL if resolution failed or nothing connected within 5 seconds.
+///   Addresses excluded by `set_dns_ip_filter` are never tried
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_happy_eyeballs('google.com', 443);
+/// -- Returns: {'family': AAAA, 'address': 2a00:1450:4001:82b::200e}
+/// ```
+struct DnsHappyEyeballs;
+
+impl VScalar for DnsHappyEyeballs {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let hostnames = read_name_column(input, 0);
+        let ports = read_column::<i32>(input, 1)
+            .into_iter()
+            .map(|port| port.map(parse_port).transpose())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(ports)
+            .map(|(hostname, port)| Some((hostname?, port?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname, port)| async move {
+            dns_happy_eyeballs_async(resolver, &hostname, port).await.ok()
+        });
+
+        let mut struct_vector = output.struct_vector();
+        let family_vector = struct_vector.child(0, size);
+        let address_vector = struct_vector.child(1, size);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(addr) => {
+                    family_vector.insert(i, address_family(addr));
+                    address_vector.insert(i, format_ip_addr(addr).as_str());
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Integer),
+            ],
+            LogicalTypeHandle::struct_type(&[
+                ("family", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("address", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ]),
+        )]
+    }
+}
+
+/// Fastest endpoint scalar function
+///
+/// Resolves the IPv4 addresses of a hostname and probes each with a TCP connect,
+/// returning the one with the lowest connect latency, e.g. to pick the closest CDN
+/// endpoint.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+/// * `port` - Optional INTEGER TCP port to probe (1-65535, default: 443)
+/// * `timeout_ms` - Optional INTEGER time budget of the probes in milliseconds (default: 1000)
+///
+/// # Returns
+/// * VARCHAR - The address that connected first, or NULL if resolution failed or no
+///   address connected within the timeout
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_fastest('google.com');
+/// -- Returns: 142.250.185.78
+///
+/// SELECT dns_lookup_fastest('dns.google', 53, 500);
+/// ```
+struct DnsLookupFastest;
+
+impl VScalar for DnsLookupFastest {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let hostnames = read_name_column(input, 0);
+        let ports = if input.num_columns() > 1 {
+            read_column::<i32>(input, 1)
+                .into_iter()
+                .map(|port| port.map(parse_port).transpose())
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            vec![Some(FASTEST_PROBE_PORT); size]
+        };
+        let timeouts = if input.num_columns() > 2 {
+            read_column::<i32>(input, 2)
+                .into_iter()
+                .map(|timeout_ms| match timeout_ms {
+                    Some(ms) if ms <= 0 => Err(format!("Timeout must be greater than 0 ms, got {}", ms)),
+                    Some(ms) => Ok(Some(Duration::from_millis(ms as u64))),
+                    None => Ok(None),
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            vec![Some(FASTEST_PROBE_TIMEOUT); size]
+        };
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(ports)
+            .zip(timeouts)
+            .map(|((hostname, port), timeout)| Some((hostname?, port?, timeout?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname, port, timeout)| async move {
+            dns_lookup_fastest_async(resolver, &hostname, port, timeout).await.ok()
+        });
+
+        let mut output_vector = output.flat_vector();
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(addr) => output_vector.insert(i, addr.to_string().as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Integer),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Integer),
+                    LogicalTypeHandle::from(LogicalTypeId::Integer),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+        ]
+    }
+}
+
+/// Fast-flux detection scalar function
+///
+/// Resolves a hostname repeatedly over a short window and reports whether its answer
+/// set changes rapidly with low TTLs, a hallmark of fast-flux malware hosting.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to sample (e.g., "example.com")
+/// * `samples` - An INTEGER number of lookups (1-100)
+/// * `interval_ms` - An INTEGER pause between lookups in milliseconds (>= 0)
+///
+/// # Returns
+/// * STRUCT(flux_score DOUBLE, distinct_ips INTEGER, min_ttl UINTEGER) - The flux score
+///   (0.0 = stable to 1.0 = changed on every sample, weighted down for TTLs above 300s),
+///   the number of distinct IPv4 addresses seen and the lowest TTL seen, or NULL if no sample resolved
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_fast_flux('google.com', 5, 1000);
+/// -- Returns: {'flux_score': 0.0, 'distinct_ips': 1, 'min_ttl': 300}
+/// ```
+///
+/// # Note
+/// Every sample bypasses the DNS cache, and the call takes about `samples * interval_ms`.
+struct DnsFastFlux;
+
+impl VScalar for DnsFastFlux {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let hostnames = read_name_column(input, 0);
+        let samples = read_column::<i32>(input, 1)
+            .into_iter()
+            .map(|samples| {
+                samples
+                    .map(|n| match n {
+                        1..=FAST_FLUX_MAX_SAMPLES => Ok(n as u32),
+                        _ => Err(format!("Invalid samples {}: must be between 1 and {}", n, FAST_FLUX_MAX_SAMPLES)),
+                    })
+                    .transpose()
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let intervals = read_column::<i32>(input, 2)
+            .into_iter()
+            .map(|interval| {
+                interval
+                    .map(|ms| match u64::try_from(ms) {
+                        Ok(ms) => Ok(Duration::from_millis(ms)),
+                        Err(_) => Err(format!("Invalid interval_ms {}: must be >= 0", ms)),
+                    })
+                    .transpose()
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(samples)
+            .zip(intervals)
+            .map(|((hostname, samples), interval)| Some((hostname?, samples?, interval?)))
+            .collect();
+
+        // Not `run_lookups`: the samples take concurrency permits per query themselves
+        let results = GLOBAL_DNS_STATE.runtime.block_on(futures::future::join_all(inputs.into_iter().map(
+            |input| async move {
+                let (hostname, samples, interval) = input?;
+                dns_fast_flux_async(&hostname, samples, interval).await.ok()
+            },
+        )));
+
+        let mut struct_vector = output.struct_vector();
+        let mut score_vector = struct_vector.child(0, size);
+        let mut distinct_vector = struct_vector.child(1, size);
+        let mut ttl_vector = struct_vector.child(2, size);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(flux) => {
+                    score_vector.as_mut_slice::<f64>()[i] = flux.flux_score;
+                    distinct_vector.as_mut_slice::<i32>()[i] = flux.distinct_ips;
+                    ttl_vector.as_mut_slice::<u32>()[i] = flux.min_ttl;
+                }
+                None => struct_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Integer),
+                LogicalTypeHandle::from(LogicalTypeId::Integer),
+            ],
+            LogicalTypeHandle::struct_type(&[
+                ("flux_score", LogicalTypeHandle::from(LogicalTypeId::Double)),
+                ("distinct_ips", LogicalTypeHandle::from(LogicalTypeId::Integer)),
+                ("min_ttl", LogicalTypeHandle::from(LogicalTypeId::UInteger)),
+            ]),
+        )]
+    }
+}
+
+/// Dual-stack consistency scalar function
+///
+/// Resolves a name once through the configured IPv4 name servers and once through
+/// the configured IPv6 name servers, and reports whether both transports return the
+/// same answers. This detects split configurations where the transport affects answers.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to check (e.g., "google.com")
+/// * `record_type` - Optional VARCHAR specifying the DNS record type (default: "A")
+///
+/// # Returns
+/// * BOOLEAN - true if both answer sets match (including both being empty), false if
+///   they differ, NULL if either transport is unavailable or a lookup fails. An
+///   unsupported record type raises an error.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_dual_stack_consistent('google.com');
+/// -- Returns: true
+///
+/// SELECT dns_dual_stack_consistent('google.com', 'MX');
+/// -- Returns: true
+/// ```
+///
+/// # Note
+/// Both lookups bypass the DNS cache. The result is NULL for every row when the
+/// configured name servers lack one of the address families.
+struct DnsDualStackConsistent;
+
+impl VScalar for DnsDualStackConsistent {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let record_types = if input.num_columns() > 1 {
+            read_varchar_column(input, 1)
+                .into_iter()
+                .map(|record_type| record_type.map(|rt| parse_record_type(&rt)).transpose())
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            vec![Some(RecordType::A); hostnames.len()]
+        };
+
+        let (Some(ipv4_resolver), Some(ipv6_resolver)) =
+            (GLOBAL_DNS_STATE.family_resolver(false), GLOBAL_DNS_STATE.family_resolver(true))
+        else {
+            write_boolean_results(output, input.len(), &[]);
+            return Ok(());
+        };
+        let (ipv4_resolver, ipv6_resolver) = (&ipv4_resolver, &ipv6_resolver);
+
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(record_types)
+            .map(|(hostname, record_type)| Some((hostname?, record_type?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname, record_type)| async move {
+            dns_dual_stack_consistent_async(ipv4_resolver, ipv6_resolver, &hostname, record_type)
+                .await
+                .ok()
+        });
+
+        write_boolean_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::from(LogicalTypeId::Boolean),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Boolean),
+            ),
+        ]
+    }
+}
+
+/// dig-style DNS query scalar function
+///
+/// Queries the first configured name server directly and returns the complete response,
+/// shaped like the output of `dig`, for people migrating from dig scripts.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the name to query (e.g., "google.com")
+/// * `record_type` - A VARCHAR specifying the DNS record type (e.g., "A", "MX", "NS")
+///
+/// # Returns
+/// * STRUCT(status VARCHAR, flags VARCHAR[], question STRUCT(name, type)[],
+///   answer/authority/additional STRUCT(name VARCHAR, type VARCHAR, ttl UINTEGER, data VARCHAR)[]) -
+///   The response code (e.g. 'NOERROR', 'NXDOMAIN'), the header flags set (qr, aa, tc, rd,
+///   ra, ad, cd) and every response section. NULL if the query fails (e.g. timeout). An
+///   unsupported record type raises an error.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_dig('google.com', 'MX');
+/// -- Returns: {'status': NOERROR, 'flags': [qr, rd, ra], 'question': [{'name': google.com., 'type': MX}],
+/// --           'answer': [{'name': google.com., 'type': MX, 'ttl': 300, 'data': 10 smtp.google.com.}], ...}
+///
+/// SELECT a.data FROM (SELECT unnest(dns_dig('google.com', 'NS').answer) AS a);
+/// ```
+///
+/// # Note
+/// The DNS cache is bypassed. Encrypted name servers (DoH3) are skipped; a configuration
+/// without plain DNS name servers returns NULL.
+struct DnsDig;
+
+impl VScalar for DnsDig {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let hostnames = read_name_column(input, 0);
+        let record_types = read_varchar_column(input, 1)
+            .into_iter()
+            .map(|record_type| record_type.map(|rt| parse_record_type(&rt)).transpose())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let results: Vec<Option<DigResponse>> = match GLOBAL_DNS_STATE.plain_name_server() {
+            Ok((server, timeout)) => {
+                let inputs: Vec<_> = hostnames
+                    .into_iter()
+                    .zip(record_types)
+                    .map(|(hostname, record_type)| Some((hostname?, record_type?)))
+                    .collect();
+                run_lookups(inputs, |(hostname, record_type)| async move {
+                    let response = raw_query(server, &hostname, record_type, timeout, None).await.ok()?;
+                    Some(DigResponse::from(&response))
+                })
+            }
+            Err(_) => (0..size).map(|_| None).collect(),
+        };
+
+        let mut struct_vector = output.struct_vector();
+        let status_vector = struct_vector.child(0, size);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(response) => status_vector.insert(i, response.status.as_str()),
+                None => struct_vector.set_null(i),
+            }
+        }
+
+        let flags: Vec<Option<Vec<String>>> = results
+            .iter()
+            .map(|result| result.as_ref().map(|r| r.flags.iter().map(|flag| flag.to_string()).collect()))
+            .collect();
+        let mut flags_vector = struct_vector.list_vector_child(1);
+        let flags_capacity: usize = flags.iter().flatten().map(|f| f.len()).sum();
+        let flags_child = flags_vector.child(flags_capacity);
+        let mut offset = 0;
+        for (i, row_flags) in flags.iter().enumerate() {
+            match row_flags {
+                Some(row_flags) => {
+                    flags_vector.set_entry(i, offset, row_flags.len());
+                    for flag in row_flags {
+                        flags_child.insert(offset, flag.as_str());
+                        offset += 1;
+                    }
+                }
+                None => flags_vector.set_null(i),
+            }
+        }
+        flags_vector.set_len(flags_capacity);
+
+        let mut question_vector = struct_vector.list_vector_child(2);
+        let question_capacity: usize = results.iter().flatten().map(|r| r.question.len()).sum();
+        let question_struct = question_vector.struct_child(question_capacity);
+        let question_name = question_struct.child(0, question_capacity);
+        let question_type = question_struct.child(1, question_capacity);
+        let mut offset = 0;
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Some(response) => {
+                    question_vector.set_entry(i, offset, response.question.len());
+                    for (name, record_type) in &response.question {
+                        question_name.insert(offset, name.as_str());
+                        question_type.insert(offset, record_type.as_str());
+                        offset += 1;
+                    }
+                }
+                None => question_vector.set_null(i),
+            }
+        }
+        question_vector.set_len(question_capacity);
+
+        let sections: [fn(&DigResponse) -> &[DigRecord]; 3] =
+            [|r| &r.answer, |r| &r.authority, |r| &r.additional];
+        for (child, section) in sections.iter().enumerate() {
+            let rows: Vec<Option<&[DigRecord]>> = results.iter().map(|r| r.as_ref().map(section)).collect();
+            write_dig_records(struct_vector.list_vector_child(3 + child), &rows);
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        let record = || {
+            LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                ("name", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("type", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("ttl", LogicalTypeHandle::from(LogicalTypeId::UInteger)),
+                ("data", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+            ]))
+        };
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ],
+            LogicalTypeHandle::struct_type(&[
+                ("status", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                ("flags", LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))),
+                (
+                    "question",
+                    LogicalTypeHandle::list(&LogicalTypeHandle::struct_type(&[
+                        ("name", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                        ("type", LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+                    ])),
+                ),
+                ("answer", record()),
+                ("authority", record()),
+                ("additional", record()),
+            ]),
+        )]
+    }
+}
+
+/// Preferred-network DNS lookup scalar function
+///
+/// Resolves all IPv4 addresses of a hostname and orders them so that addresses
+/// inside a preferred network come first, e.g. for latency-aware endpoint selection.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
+/// * `preferred_cidr` - A VARCHAR containing the preferred network (e.g., "10.0.0.0/8")
+///
+/// # Returns
+/// * VARCHAR[] - All IPv4 addresses, preferred ones first; both groups keep resolver order.
+///   Returns NULL on lookup error. An invalid CIDR raises an error.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_lookup_prefer('cloudflare.com', '104.16.132.0/24');
+/// -- Returns: [104.16.132.229, 104.16.133.229]
+/// ```
+struct DnsLookupPrefer;
+
+impl VScalar for DnsLookupPrefer {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let cidrs = read_varchar_column(input, 1)
+            .into_iter()
+            .map(|cidr| cidr.map(|c| parse_cidr(&c)).transpose())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(cidrs)
+            .map(|(hostname, cidr)| Some((hostname?, cidr?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname, cidr)| async move {
+            dns_lookup_prefer_async(resolver, &hostname, cidr).await.ok()
+        });
+
+        write_varchar_list_results(output, input.len(), &results);
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ],
+            LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        )]
+    }
+}
+
+/// DNS response code scalar function
+///
+/// Returns the response code (RCODE) the server answered a query with.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname to query (e.g., "google.com")
+/// * `record_type` - Optional VARCHAR specifying the DNS record type (default: "A")
+///
+/// # Returns
+/// * VARCHAR - The response code mnemonic (NOERROR, NXDOMAIN, SERVFAIL, REFUSED, NOTIMP, ...),
+///   or NULL when no response was received. An unsupported record type raises an error.
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_rcode('google.com', 'A');
+/// -- Returns: NOERROR
+///
+/// SELECT dns_rcode('this-domain-definitely-does-not-exist-12345.com', 'A');
+/// -- Returns: NXDOMAIN
+/// ```
+struct DnsRcode;
+
+impl VScalar for DnsRcode {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let record_types = if input.num_columns() > 1 {
+            read_varchar_column(input, 1)
+                .into_iter()
+                .map(|record_type| record_type.map(|rt| parse_record_type(&rt)).transpose())
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            vec![Some(RecordType::A); hostnames.len()]
+        };
+        let mut output_vector = output.flat_vector();
+
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames
+            .into_iter()
+            .zip(record_types)
+            .map(|(hostname, record_type)| Some((hostname?, record_type?)))
+            .collect();
+
+        let results = run_lookups(inputs, |(hostname, record_type)| async move {
+            dns_rcode_async(resolver, &hostname, record_type).await.ok()
+        });
+
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Some(rcode) => output_vector.insert(i, rcode.as_str()),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+        ]
+    }
+}
+
+/// Cache statistics scalar function
+///
+/// Reports how effective the session DNS cache is, to tune `set_dns_cache_size`
+/// and verify the cache is helping a workload.
+///
+/// # Returns
+/// * STRUCT(entries INTEGER, hits BIGINT, misses BIGINT, evictions BIGINT, hit_ratio DOUBLE) -
+///   The number of unexpired cached answers, the lookups answered from and past the cache,
+///   the entries evicted because the cache was full, and hits / (hits + misses)
+///   (0.0 before the first lookup)
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_cache_stats();
+/// -- Returns: {'entries': 12, 'hits': 30, 'misses': 12, 'evictions': 0, 'hit_ratio': 0.714}
+/// ```
+///
+/// # Note
+/// Only lookups through the session resolver are counted. Changing the configuration
+/// or cache size empties the cache but keeps the counters.
+struct DnsCacheStats;
+
+impl VScalar for DnsCacheStats {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let stats = GLOBAL_DNS_STATE.cache_stats.snapshot();
+
+        let struct_vector = output.struct_vector();
+        let mut entries_vector = struct_vector.child(0, size);
+        let mut hits_vector = struct_vector.child(1, size);
+        let mut misses_vector = struct_vector.child(2, size);
+        let mut evictions_vector = struct_vector.child(3, size);
+        let mut ratio_vector = struct_vector.child(4, size);
+        for i in 0..size {
+            entries_vector.as_mut_slice::<i32>()[i] = stats.entries;
+            hits_vector.as_mut_slice::<i64>()[i] = stats.hits;
+            misses_vector.as_mut_slice::<i64>()[i] = stats.misses;
+            evictions_vector.as_mut_slice::<i64>()[i] = stats.evictions;
+            ratio_vector.as_mut_slice::<f64>()[i] = stats.hit_ratio;
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![],
+            LogicalTypeHandle::struct_type(&[
+                ("entries", LogicalTypeHandle::from(LogicalTypeId::Integer)),
+                ("hits", LogicalTypeHandle::from(LogicalTypeId::Bigint)),
+                ("misses", LogicalTypeHandle::from(LogicalTypeId::Bigint)),
+                ("evictions", LogicalTypeHandle::from(LogicalTypeId::Bigint)),
+                ("hit_ratio", LogicalTypeHandle::from(LogicalTypeId::Double)),
+            ]),
+        )]
+    }
+
+    fn volatile() -> bool {
+        true
+    }
+}
+
+/// Cache clearing scalar function
+///
+/// Empties the session DNS cache and the DNS caches of per-call resolvers, so the
+/// next lookups query the upstream servers again, e.g. after changing a record.
+///
+/// # Returns
+/// * VARCHAR - A confirmation message
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_cache_clear();
+/// -- Returns: DNS cache cleared
+/// ```
+///
+/// # Note
+/// The cache statistics counters are kept, see `dns_cache_stats`.
+struct DnsCacheClear;
+
+impl VScalar for DnsCacheClear {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let message = match GLOBAL_DNS_STATE.clear_dns_cache() {
+            Ok(()) => "DNS cache cleared".to_string(),
+            Err(e) => format!("Failed to clear DNS cache: {}", e),
+        };
+
+        let output_vector = output.flat_vector();
+        for i in 0..size {
+            output_vector.insert(i, message.as_str());
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+
+    fn volatile() -> bool {
+        true
+    }
+}
+
+/// Resolver cache statistics scalar function
+///
+/// Reports how often per-call resolvers are reused, to tune `set_dns_resolver_cache_size`.
+///
+/// # Returns
+/// * STRUCT(entries INTEGER, capacity INTEGER, hits BIGINT, misses BIGINT, evictions BIGINT) -
+///   The number of cached resolvers, the cache size, the lookups of a resolver that
+///   found and did not find one cached, and the resolvers evicted as least recently used
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_resolver_cache_stats();
+/// -- Returns: {'entries': 2, 'capacity': 64, 'hits': 10, 'misses': 2, 'evictions': 0}
+/// ```
+struct DnsResolverCacheStats;
+
+impl VScalar for DnsResolverCacheStats {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let resolvers = GLOBAL_DNS_STATE.spec_resolvers.lock().map_err(|_| "Resolver spec cache poisoned")?;
+        let entries = i32::try_from(resolvers.entries.len()).unwrap_or(i32::MAX);
+        let capacity = i32::try_from(resolvers.capacity).unwrap_or(i32::MAX);
+
+        let struct_vector = output.struct_vector();
+        let mut entries_vector = struct_vector.child(0, size);
+        let mut capacity_vector = struct_vector.child(1, size);
+        let mut hits_vector = struct_vector.child(2, size);
+        let mut misses_vector = struct_vector.child(3, size);
+        let mut evictions_vector = struct_vector.child(4, size);
+        for i in 0..size {
+            entries_vector.as_mut_slice::<i32>()[i] = entries;
+            capacity_vector.as_mut_slice::<i32>()[i] = capacity;
+            hits_vector.as_mut_slice::<i64>()[i] = resolvers.hits as i64;
+            misses_vector.as_mut_slice::<i64>()[i] = resolvers.misses as i64;
+            evictions_vector.as_mut_slice::<i64>()[i] = resolvers.evictions as i64;
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![],
+            LogicalTypeHandle::struct_type(&[
+                ("entries", LogicalTypeHandle::from(LogicalTypeId::Integer)),
+                ("capacity", LogicalTypeHandle::from(LogicalTypeId::Integer)),
+                ("hits", LogicalTypeHandle::from(LogicalTypeId::Bigint)),
+                ("misses", LogicalTypeHandle::from(LogicalTypeId::Bigint)),
+                ("evictions", LogicalTypeHandle::from(LogicalTypeId::Bigint)),
+            ]),
+        )]
+    }
+
+    fn volatile() -> bool {
+        true
+    }
+}
+
+/// TLD classification scalar function
+///
+/// Classifies the top-level domain of a hostname for segmenting domain datasets.
+/// This is pure computation over embedded TLD lists; no DNS query is made.
+///
+/// # Arguments
+/// * `hostname` - A VARCHAR containing the hostname (e.g., "www.example.co.uk")
+///
+/// # Returns
+/// * VARCHAR - 'gtld' (legacy generic, e.g. com, org), 'cctld' (country code, e.g. uk, de),
+///   'new_gtld' (e.g. app, dev), 'special' (e.g. localhost, onion, arpa), or NULL for
+///   empty names, IP addresses and invalid TLDs
+///
+/// # Examples
+/// ```sql
+/// SELECT dns_tld_type('www.bbc.co.uk');
+/// -- Returns: cctld
+///
+/// SELECT dns_tld_type('web.dev');
+/// -- Returns: new_gtld
+/// ```
+struct DnsTldType;
+
+impl VScalar for DnsTldType {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let hostnames = read_name_column(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, hostname) in hostnames.iter().enumerate() {
+            match hostname.as_deref().and_then(classify_tld) {
+                Some(tld_type) => output_vector.insert(i, tld_type),
+                None => output_vector.set_null(i),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Configuration update scalar function
+///
+/// Updates the DNS resolver configuration for all subsequent DNS queries.
+/// Supported preset configurations: 'default', 'system', 'google', 'cloudflare', 'quad9'
+///
+/// # Arguments
+/// * `preset` - A VARCHAR containing the configuration preset name
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// -- Use Google DNS servers (8.8.8.8, 8.8.4.4)
+/// SELECT set_dns_config('google');
+///
+/// -- Use Cloudflare DNS servers (1.1.1.1, 1.0.0.1)
+/// SELECT set_dns_config('cloudflare');
+///
+/// -- Use Quad9 DNS servers (9.9.9.9, 149.112.112.112)
+/// SELECT set_dns_config('quad9');
+///
+/// -- Use the default configuration (Google Public DNS)
+/// SELECT set_dns_config('default');
+///
+/// -- Use the name servers of the system configuration (/etc/resolv.conf)
+/// SELECT set_dns_config('system');
+/// ```
+///
+/// # Note
+/// Changing configuration clears the DNS cache. If the system configuration is
+/// missing or lists no name servers, 'system' falls back to the 'default' servers and
+/// the returned message says so.
+struct SetDnsConfig;
+
+impl VScalar for SetDnsConfig {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let input_vector = input.flat_vector(0);
+        let mut output_vector = output.flat_vector();
+
+        // Get input strings
+        let values = input_vector.as_slice_with_len::<duckdb_string_t>(size);
+
+        for (i, value) in values.iter().enumerate() {
+            if input_vector.row_is_null(i as u64) {
+                output_vector.set_null(i);
+                continue;
+            }
+
+            let preset = DuckString::new(&mut { *value }).as_str().trim().to_lowercase();
+
+            if !DNS_PRESETS.contains(&preset.as_str()) {
+                let error_msg = format!("Unknown preset '{}'. Supported presets: default, system, google, cloudflare, quad9", preset);
+                output_vector.insert(i, &error_msg);
+                continue;
+            }
+
+            // Update the global resolver configuration, keeping the current protocol
+            // This is a lock-free atomic operation - extremely fast!
+            match GLOBAL_DNS_STATE.set_dns_preset(&preset) {
+                Ok(_) => {
+                    let mut success_msg = format!("DNS configuration updated to '{}'", preset);
+                    if preset == "system" {
+                        if let (_, Some(e)) = system_config_or_default(read_system_config()) {
+                            success_msg.push_str(&format!(
+                                " (system configuration unusable: {}, falling back to 'default')",
+                                e
+                            ));
+                        }
+                    }
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update DNS configuration: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Protocol configuration scalar function
+///
+/// Updates the transport protocol used to reach the DNS servers of the current preset.
+/// Supported protocols: 'udp' (default), 'doh3' (DNS-over-HTTPS over HTTP/3, requires
+/// building with the `doh3` Cargo feature and a provider preset)
+///
+/// # Arguments
+/// * `protocol` - A VARCHAR containing the protocol name
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// -- Use Cloudflare over DNS-over-HTTP/3
+/// SELECT set_dns_config('cloudflare');
+/// SELECT set_dns_protocol('doh3');
+/// -- Returns: DNS protocol updated to 'doh3'
+///
+/// -- Back to plain DNS
+/// SELECT set_dns_protocol('udp');
+/// ```
+///
+/// # Note
+/// Changing the protocol clears the DNS cache. The protocol is kept when switching presets.
+struct SetDnsProtocol;
+
+impl VScalar for SetDnsProtocol {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let protocols = read_varchar_column(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, protocol) in protocols.iter().enumerate() {
+            let Some(protocol) = protocol else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            let result = parse_dns_protocol(protocol).and_then(|protocol| {
+                GLOBAL_DNS_STATE.set_dns_protocol(protocol)?;
+                Ok(protocol)
+            });
+            match result {
+                Ok(protocol) => {
+                    let success_msg = format!("DNS protocol updated to '{}'", protocol.name());
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update DNS protocol: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// IPv6 zone id configuration scalar function
+///
+/// Sets the zone id (interface name or index) appended to link-local IPv6
+/// addresses (fe80::/10) in AAAA results, e.g. `fe80::1%eth0`. DNS answers never
+/// carry a zone id themselves, so this is how internal link-local records are
+/// made dialable. An empty string resets to unscoped addresses (the default).
+///
+/// # Arguments
+/// * `zone_id` - A VARCHAR containing the zone id, or '' to strip it
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_ipv6_zone_id('eth0');
+/// -- Returns: IPv6 zone id updated to 'eth0'
+///
+/// SELECT set_dns_ipv6_zone_id('');
+/// -- Returns: IPv6 zone id cleared
+/// ```
+struct SetDnsIpv6ZoneId;
+
+impl VScalar for SetDnsIpv6ZoneId {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let zone_ids = read_varchar_column(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, zone_id) in zone_ids.iter().enumerate() {
+            let Some(zone_id) = zone_id else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            let zone_id = zone_id.trim().trim_start_matches('%');
+            let setting = (!zone_id.is_empty()).then(|| zone_id.to_string());
+            match GLOBAL_DNS_STATE.set_dns_ipv6_zone_id(setting) {
+                Ok(_) if zone_id.is_empty() => output_vector.insert(i, "IPv6 zone id cleared"),
+                Ok(_) => {
+                    let success_msg = format!("IPv6 zone id updated to '{}'", zone_id);
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update IPv6 zone id: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// IP filter configuration scalar function
+///
+/// Sets the networks whose addresses are dropped from lookup results, e.g. to ignore
+/// known CDN or internal ranges when analyzing where domains point. The filter applies
+/// to the addresses returned by `dns_lookup` and `dns_lookup_all`, including A and
+/// AAAA records queried by record type. An empty list clears the filter.
+///
+/// # Arguments
+/// * `cidrs` - A VARCHAR[] of networks in CIDR notation; bare addresses match a single host
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_ip_filter(['10.0.0.0/8', '104.16.0.0/13']);
+/// -- Returns: DNS IP filter updated to 2 networks
+///
+/// SELECT set_dns_ip_filter([]);
+/// -- Returns: DNS IP filter cleared
+/// ```
+///
+/// # Note
+/// When every address is filtered, `dns_lookup_all` returns an empty list (the name did
+/// resolve) while `dns_lookup` returns NULL.
+struct SetDnsIpFilter;
+
+impl VScalar for SetDnsIpFilter {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let cidr_lists = read_varchar_list_column(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, cidrs) in cidr_lists.iter().enumerate() {
+            let Some(cidrs) = cidrs else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            match cidrs.iter().map(|cidr| parse_cidr(cidr)).collect::<std::result::Result<Vec<_>, _>>() {
+                Ok(filter) if filter.is_empty() => {
+                    GLOBAL_DNS_STATE.ip_filter.store(Arc::new(filter));
+                    output_vector.insert(i, "DNS IP filter cleared");
+                }
+                Ok(filter) => {
+                    let noun = if filter.len() == 1 { "network" } else { "networks" };
+                    let success_msg = format!("DNS IP filter updated to {} {}", filter.len(), noun);
+                    GLOBAL_DNS_STATE.ip_filter.store(Arc::new(filter));
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update DNS IP filter: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Search order configuration scalar function
+///
+/// Controls whether a relative name is tried bare before or after appending the
+/// search domains of the configuration, to replicate a particular host's resolver.
+///
+/// # Arguments
+/// * `order` - A VARCHAR: 'ndots' (default, resolv.conf behavior), 'bare_first' or 'search_first'
+/// * `ndots` - Optional BIGINT threshold for 'ndots' (0-15, default 1): names with more
+///   labels than this are tried bare first, shorter names with the search domains first
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_search_order('search_first');
+/// -- Returns: DNS search order updated to 'search_first'
+///
+/// -- Match a Kubernetes pod's resolv.conf
+/// SELECT set_dns_search_order('ndots', 5);
+/// -- Returns: DNS search order updated to 'ndots' (ndots=5)
+/// ```
+///
+/// # Note
+/// Fully qualified names (with a trailing dot) are never expanded. The built-in presets
+/// have no search domains, so the order only matters for configurations that do.
+/// Changing the order clears the DNS cache.
+struct SetDnsSearchOrder;
+
+impl VScalar for SetDnsSearchOrder {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let orders = read_varchar_column(input, 0);
+        let ndots = if input.num_columns() > 1 {
+            read_column::<i64>(input, 1)
+        } else {
+            vec![None; orders.len()]
+        };
+        let mut output_vector = output.flat_vector();
+
+        for (i, (order, ndots)) in orders.iter().zip(ndots).enumerate() {
+            let Some(order) = order else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            let result = parse_search_order(order, ndots).and_then(|order| {
+                GLOBAL_DNS_STATE.set_dns_search_order(order)?;
+                Ok(order)
+            });
+            match result {
+                Ok(order) => {
+                    let success_msg = format!("DNS search order updated to {}", order.describe());
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update DNS search order: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![
+            ScalarFunctionSignature::exact(
+                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+            ScalarFunctionSignature::exact(
+                vec![
+                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
+                    LogicalTypeHandle::from(LogicalTypeId::Bigint),
+                ],
+                LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            ),
+        ]
+    }
+}
+
+/// Hosts file configuration scalar function
+///
+/// Controls which static host entries win over DNS, e.g. names only defined in
+/// `/etc/hosts`, or a test fixture of names that must resolve without the network.
+///
+/// # Arguments
+/// * `source` - A VARCHAR: 'system' (default) reads the system hosts file, 'none' resolves
+///   every name through DNS, any other value is the path of a hosts-format file to load
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_hosts('none');
+/// -- Returns: DNS hosts source updated to 'none'
+///
+/// SELECT set_dns_hosts('/tmp/test.hosts');
+/// -- Returns: DNS hosts source updated to file '/tmp/test.hosts'
+/// ```
+///
+/// # Note
+/// A hosts file is read once when set; call the function again to pick up changes.
+/// Changing the hosts source clears the DNS cache.
+struct SetDnsHosts;
+
+impl VScalar for SetDnsHosts {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let sources = read_varchar_column(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, source) in sources.iter().enumerate() {
+            let Some(source) = source else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            let result = parse_hosts_source(source).and_then(|source| {
+                let description = source.describe();
+                GLOBAL_DNS_STATE.set_dns_hosts(source)?;
+                Ok(description)
+            });
+            match result {
+                Ok(description) => {
+                    let success_msg = format!("DNS hosts source updated to {}", description);
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update DNS hosts source: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Concurrency limit configuration scalar function
+///
+/// Updates the concurrency limit for DNS lookup operations to prevent TCP connection exhaustion.
+///
+/// # Arguments
+/// * `limit` - A BIGINT specifying the maximum number of concurrent DNS requests (must be > 0)
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// -- Set concurrency limit to 100
+/// SELECT set_dns_concurrency_limit(100);
+/// -- Returns: Concurrency limit updated to 100
+///
+/// -- Set concurrency limit to 500 for high-throughput scenarios
+/// SELECT set_dns_concurrency_limit(500);
+/// -- Returns: Concurrency limit updated to 500
+///
+/// -- Reset to default (50)
+/// SELECT set_dns_concurrency_limit(50);
+/// -- Returns: Concurrency limit updated to 50
+///
+/// -- Invalid limit returns error
+/// SELECT set_dns_concurrency_limit(0);
+/// -- Returns: Concurrency limit must be greater than 0
+/// ```
+///
+/// # Note
+/// This setting applies globally to all DNS lookup operations and takes effect immediately.
+struct SetConcurrencyLimit;
+
+impl VScalar for SetConcurrencyLimit {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let size = input.len();
+        let input_vector = input.flat_vector(0);
+        let mut output_vector = output.flat_vector();
+
+        // Get input values
+        let values = input_vector.as_slice_with_len::<i64>(size);
+
+        for (i, &limit) in values.iter().enumerate() {
+            if input_vector.row_is_null(i as u64) {
+                output_vector.set_null(i);
+                continue;
+            }
+
+            if limit <= 0 {
+                let error_msg = "Concurrency limit must be greater than 0";
+                output_vector.insert(i, error_msg);
+                continue;
+            }
+
+            // Update the global concurrency limit
+            // This is a lock-free atomic operation - extremely fast!
+            match GLOBAL_DNS_STATE.set_dns_concurrency_limit(limit as usize) {
+                Ok(_) => {
+                    let success_msg = format!("Concurrency limit updated to {}", limit);
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update concurrency limit: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Cache size configuration scalar function
+///
+/// Updates the DNS cache size for the resolver. The cache stores DNS query results
+/// to improve performance by avoiding repeated lookups for the same queries.
+///
+/// # Arguments
+/// * `size` - A BIGINT specifying the maximum number of cached DNS queries (must be > 0)
 ///
 /// # Returns
-/// * VARCHAR - The resolved hostname (e.g., "dns.google"), or NULL on error
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// -- Set cache size to 4096 (default)
+/// SELECT set_dns_cache_size(4096);
+/// -- Returns: DNS cache size updated to 4096
 ///
-/// # Example
-/// ```sql
-/// SELECT reverse_dns_lookup('8.8.8.8') as hostname;
-/// -- Returns: dns.google
+/// -- Set cache size to 8192 for larger workloads
+/// SELECT set_dns_cache_size(8192);
+/// -- Returns: DNS cache size updated to 8192
 /// ```
-struct ReverseDnsLookup;
+///
+/// # Note
+/// Changing the cache size rebuilds the resolver and clears the existing cache.
+/// This operation takes effect immediately for all subsequent DNS queries.
+struct SetDnsCacheSize;
 
-impl VScalar for ReverseDnsLookup {
+impl VScalar for SetDnsCacheSize {
     type State = ();
 
     unsafe fn invoke(
@@ -397,44 +9254,107 @@ impl VScalar for ReverseDnsLookup {
         let input_vector = input.flat_vector(0);
         let mut output_vector = output.flat_vector();
 
-        // Get input strings
-        let values = input_vector.as_slice_with_len::<duckdb_string_t>(size);
-        let strings: Vec<String> = values
-            .iter()
-            .map(|ptr| DuckString::new(&mut { *ptr }).as_str().to_string())
-            .collect();
+        // Get input values
+        let values = input_vector.as_slice_with_len::<i64>(size);
 
-        // Use the global resolver state - load once for all lookups
-        let resolver = &GLOBAL_DNS_STATE.resolver;
-        let semaphore = GLOBAL_DNS_STATE.concurrency_semaphore.load();
+        for (i, &cache_size) in values.iter().enumerate() {
+            if input_vector.row_is_null(i as u64) {
+                output_vector.set_null(i);
+                continue;
+            }
 
-        // Process all lookups concurrently with semaphore-controlled execution
-        let futures: Vec<_> = strings
-            .iter()
-            .enumerate()
-            .map(|(i, ip_address)| {
-                let is_null = input_vector.row_is_null(i as u64);
-                let ip_address = ip_address.clone();
-                let sem = semaphore.clone();
-                async move {
-                    if is_null {
-                        (i, None)
-                    } else {
-                        let _permit = sem.acquire().await.unwrap();
-                        let result = reverse_dns_lookup_async(resolver, &ip_address).await;
-                        (i, result.ok())
-                    }
+            if cache_size <= 0 {
+                let error_msg = "Cache size must be greater than 0";
+                output_vector.insert(i, error_msg);
+                continue;
+            }
+
+            // Update the global cache size
+            // This is a lock-free atomic operation - extremely fast!
+            match GLOBAL_DNS_STATE.set_dns_cache_size(cache_size as usize) {
+                Ok(_) => {
+                    let success_msg = format!("DNS cache size updated to {}", cache_size);
+                    output_vector.insert(i, &success_msg);
                 }
-            })
-            .collect();
+                Err(e) => {
+                    let error_msg = format!("Failed to update cache size: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
 
-        let results = GLOBAL_DNS_STATE.runtime.block_on(async { futures::future::join_all(futures).await });
+        Ok(())
+    }
 
-        // Write results to output
-        for (i, result) in results.into_iter().take(size) {
-            match result {
-                Some(hostname) => output_vector.insert(i, hostname.as_str()),
-                None => output_vector.set_null(i),
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Largest negative cache TTL accepted by `set_dns_negative_cache_ttl` (one day)
+const MAX_NEGATIVE_CACHE_TTL_SECS: i64 = 86400;
+
+/// Negative cache TTL configuration scalar function
+///
+/// Sets how long NXDOMAIN and empty answers stay cached, so repeated scans over
+/// failing domains are answered from the cache instead of the network.
+///
+/// # Arguments
+/// * `seconds` - A BIGINT TTL between 0 and 86400; 0 disables negative caching
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_negative_cache_ttl(300);
+/// -- Returns: DNS negative cache TTL updated to 300 seconds
+/// ```
+///
+/// # Note
+/// By default negative answers are cached for the SOA minimum TTL of the zone. The TTL
+/// only applies to negative answers that carry an SOA record, as answers from recursive
+/// resolvers do. Changing the TTL rebuilds the resolver and clears the existing cache.
+struct SetDnsNegativeCacheTtl;
+
+impl VScalar for SetDnsNegativeCacheTtl {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let ttls = read_column::<i64>(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, ttl) in ttls.into_iter().enumerate() {
+            let Some(ttl) = ttl else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            if !(0..=MAX_NEGATIVE_CACHE_TTL_SECS).contains(&ttl) {
+                let error_msg = format!(
+                    "Negative cache TTL must be between 0 and {} seconds",
+                    MAX_NEGATIVE_CACHE_TTL_SECS
+                );
+                output_vector.insert(i, &error_msg);
+                continue;
+            }
+
+            match GLOBAL_DNS_STATE.set_dns_negative_cache_ttl(Duration::from_secs(ttl as u64)) {
+                Ok(_) => {
+                    let success_msg = format!("DNS negative cache TTL updated to {} seconds", ttl);
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update negative cache TTL: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
             }
         }
 
@@ -443,46 +9363,251 @@ impl VScalar for ReverseDnsLookup {
 
     fn signatures() -> Vec<ScalarFunctionSignature> {
         vec![ScalarFunctionSignature::exact(
-            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
             LogicalTypeHandle::from(LogicalTypeId::Varchar),
         )]
     }
 }
 
-/// Forward DNS lookup scalar function
+/// Largest query timeout accepted by `set_dns_timeout` (one minute)
+const MAX_QUERY_TIMEOUT_MS: i64 = 60_000;
+
+/// Query timeout configuration scalar function
 ///
-/// Performs DNS lookups to resolve hostnames to IP addresses or other DNS record types.
+/// Sets how long the session resolver waits for a name server to answer before it
+/// retries or gives up, for all functions using the session configuration.
 ///
 /// # Arguments
-/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
-/// * `record_type` - Optional VARCHAR specifying the DNS record type (e.g., "A", "MX", "TXT", "CNAME")
+/// * `timeout_ms` - A BIGINT timeout between 1 and 60000 milliseconds (default: 5000)
 ///
 /// # Returns
-/// * VARCHAR - The first resolved record:
-///   - Without record_type: First IPv4 address
-///   - With record_type: First record of specified type
-///   - Returns NULL on error
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_timeout(1000);
+/// -- Returns: DNS query timeout updated to 1000 ms
+/// ```
+///
+/// # Note
+/// The timeout applies per attempt, see `set_dns_attempts`. Resolver specs keep their
+/// own `timeout` option. Changing the timeout rebuilds the resolver and clears the
+/// existing cache.
+struct SetDnsTimeout;
+
+impl VScalar for SetDnsTimeout {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let timeouts = read_column::<i64>(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, timeout_ms) in timeouts.into_iter().enumerate() {
+            let Some(timeout_ms) = timeout_ms else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            if !(1..=MAX_QUERY_TIMEOUT_MS).contains(&timeout_ms) {
+                let error_msg = format!("Timeout must be between 1 and {} ms", MAX_QUERY_TIMEOUT_MS);
+                output_vector.insert(i, &error_msg);
+                continue;
+            }
+
+            match GLOBAL_DNS_STATE.set_dns_timeout(Duration::from_millis(timeout_ms as u64)) {
+                Ok(_) => {
+                    let success_msg = format!("DNS query timeout updated to {} ms", timeout_ms);
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update query timeout: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Largest number of attempts accepted by `set_dns_attempts`, as for resolver specs
+const MAX_QUERY_ATTEMPTS: i64 = 10;
+
+/// Query attempts configuration scalar function
+///
+/// Sets how many times the session resolver tries a query before a lookup fails, for
+/// all functions using the session configuration.
+///
+/// # Arguments
+/// * `attempts` - A BIGINT between 1 and 10 (default: 2)
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_attempts(1);
+/// -- Returns: DNS query attempts updated to 1
+/// ```
+///
+/// # Note
+/// Resolver specs keep their own `attempts` option. Changing the attempts rebuilds the
+/// resolver and clears the existing cache.
+struct SetDnsAttempts;
+
+impl VScalar for SetDnsAttempts {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let attempts = read_column::<i64>(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, attempts) in attempts.into_iter().enumerate() {
+            let Some(attempts) = attempts else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            if !(1..=MAX_QUERY_ATTEMPTS).contains(&attempts) {
+                let error_msg = format!("Attempts must be between 1 and {}", MAX_QUERY_ATTEMPTS);
+                output_vector.insert(i, &error_msg);
+                continue;
+            }
+
+            match GLOBAL_DNS_STATE.set_dns_attempts(attempts as usize) {
+                Ok(_) => {
+                    let success_msg = format!("DNS query attempts updated to {}", attempts);
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update query attempts: {}", e);
+                    output_vector.insert(i, &error_msg);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Largest number of per-call resolvers accepted by `set_dns_resolver_cache_size`
+const MAX_RESOLVER_CACHE_SIZE: i64 = 4096;
+
+/// Resolver cache size configuration scalar function
+///
+/// Sets how many per-call resolvers (resolver specs, name server lists, DoH endpoints
+/// and DoT servers) are kept for reuse.
+///
+/// # Arguments
+/// * `size` - A BIGINT number of resolvers, 1-4096 (default: 64)
+///
+/// # Returns
+/// * VARCHAR - Success message or error description
+///
+/// # Examples
+/// ```sql
+/// SELECT set_dns_resolver_cache_size(256);
+/// -- Returns: Resolver cache size updated to 256
+/// ```
+///
+/// # Note
+/// Shrinking the cache evicts the least recently used resolvers, along with their DNS
+/// caches. The session resolver is not affected.
+struct SetDnsResolverCacheSize;
+
+impl VScalar for SetDnsResolverCacheSize {
+    type State = ();
+
+    unsafe fn invoke(
+        _state: &Self::State,
+        input: &mut DataChunkHandle,
+        output: &mut dyn WritableVector,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let sizes = read_column::<i64>(input, 0);
+        let mut output_vector = output.flat_vector();
+
+        for (i, size) in sizes.into_iter().enumerate() {
+            let Some(size) = size else {
+                output_vector.set_null(i);
+                continue;
+            };
+
+            if !(1..=MAX_RESOLVER_CACHE_SIZE).contains(&size) {
+                let error_msg = format!("Resolver cache size must be between 1 and {}", MAX_RESOLVER_CACHE_SIZE);
+                output_vector.insert(i, &error_msg);
+                continue;
+            }
+
+            match GLOBAL_DNS_STATE.spec_resolvers.lock() {
+                Ok(mut resolvers) => {
+                    resolvers.set_capacity(size as usize);
+                    let success_msg = format!("Resolver cache size updated to {}", size);
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(_) => output_vector.insert(i, "Failed to update resolver cache size: cache poisoned"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signatures() -> Vec<ScalarFunctionSignature> {
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
+    }
+}
+
+/// Address family configuration scalar function
+///
+/// Controls which addresses `dns_lookup` and `dns_lookup_all` return when no record type
+/// is given, for users on IPv6 or dual-stack networks.
+///
+/// # Arguments
+/// * `family` - A VARCHAR: 'ipv4' (default), 'ipv6' or 'any' (IPv4 addresses followed by
+///   IPv6 addresses)
 ///
-/// # Supported Record Types
-/// A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, CAA
+/// # Returns
+/// * VARCHAR - Success message or error description
 ///
 /// # Examples
 /// ```sql
-/// -- Get first IPv4 address
-/// SELECT dns_lookup('google.com') as ip;
-/// -- Returns: 142.251.209.142
+/// SELECT set_dns_address_family('any');
+/// -- Returns: DNS address family updated to 'any'
 ///
-/// -- Get TXT record
-/// SELECT dns_lookup('_dmarc.google.com', 'TXT') as txt;
-/// -- Returns: v=DMARC1; p=reject; ...
-///
-/// -- Get MX record
-/// SELECT dns_lookup('google.com', 'MX') as mx;
-/// -- Returns: 10 smtp.google.com.
+/// SELECT dns_lookup_all('dns.google');
+/// -- Returns: [8.8.8.8, 8.8.4.4, 2001:4860:4860::8888, 2001:4860:4860::8844]
 /// ```
-struct DnsLookup;
+///
+/// # Note
+/// The family does not change the resolver, so the DNS cache is kept. Functions for a
+/// specific family, such as `dns_lookup_aaaa`, are not affected.
+struct SetDnsAddressFamily;
 
-impl VScalar for DnsLookup {
+impl VScalar for SetDnsAddressFamily {
     type State = ();
 
     unsafe fn invoke(
@@ -490,81 +9615,25 @@ impl VScalar for DnsLookup {
         input: &mut DataChunkHandle,
         output: &mut dyn WritableVector,
     ) -> std::result::Result<(), Box<dyn Error>> {
-        let size = input.len();
-        let hostname_vector = input.flat_vector(0);
+        let families = read_varchar_column(input, 0);
         let mut output_vector = output.flat_vector();
 
-        // Get hostname strings
-        let hostname_values = hostname_vector.as_slice_with_len::<duckdb_string_t>(size);
-        let hostnames: Vec<String> = hostname_values
-            .iter()
-            .map(|ptr| DuckString::new(&mut { *ptr }).as_str().to_string())
-            .collect();
-
-        // Check if we have a second parameter (record_type)
-        let record_types: Option<Vec<Option<String>>> = if input.num_columns() > 1 {
-            let record_type_vector = input.flat_vector(1);
-            let record_type_values = record_type_vector.as_slice_with_len::<duckdb_string_t>(size);
-            Some(
-                (0..size)
-                    .map(|i| {
-                        if record_type_vector.row_is_null(i as u64) {
-                            None
-                        } else {
-                            Some(
-                                DuckString::new(&mut { record_type_values[i] })
-                                    .as_str()
-                                    .to_string(),
-                            )
-                        }
-                    })
-                    .collect(),
-            )
-        } else {
-            None
-        };
-
-        // Use the global resolver state - load once for all lookups
-        let resolver = &GLOBAL_DNS_STATE.resolver;
-        let semaphore = GLOBAL_DNS_STATE.concurrency_semaphore.load();
+        for (i, family) in families.iter().enumerate() {
+            let Some(family) = family else {
+                output_vector.set_null(i);
+                continue;
+            };
 
-        // Process all lookups concurrently with semaphore-controlled execution
-        let futures: Vec<_> = hostnames
-            .iter()
-            .enumerate()
-            .map(|(i, hostname)| {
-                let is_null = hostname_vector.row_is_null(i as u64);
-                let hostname = hostname.clone();
-                let record_type_opt = record_types.as_ref().and_then(|rt| rt[i].clone());
-                let sem = semaphore.clone();
-                async move {
-                    if is_null {
-                        (i, None)
-                    } else {
-                        let _permit = sem.acquire().await.unwrap();
-                        let result = if let Some(record_type_str) = record_type_opt {
-                            match parse_record_type(&record_type_str) {
-                                Ok(record_type) => {
-                                    dns_lookup_with_type_async(resolver, &hostname, record_type).await
-                                }
-                                Err(e) => Err(e),
-                            }
-                        } else {
-                            dns_lookup_async(resolver, &hostname).await
-                        };
-                        (i, result.ok())
-                    }
+            match parse_address_family(family) {
+                Ok(family) => {
+                    GLOBAL_DNS_STATE.address_family.store(Arc::new(family));
+                    let success_msg = format!("DNS address family updated to '{}'", family.as_str());
+                    output_vector.insert(i, &success_msg);
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to update DNS address family: {}", e);
+                    output_vector.insert(i, &error_msg);
                 }
-            })
-            .collect();
-
-        let results = GLOBAL_DNS_STATE.runtime.block_on(async { futures::future::join_all(futures).await });
-
-        // Write results to output
-        for (i, result) in results.into_iter().take(size) {
-            match result {
-                Some(data) => output_vector.insert(i, data.as_str()),
-                None => output_vector.set_null(i),
             }
         }
 
@@ -572,521 +9641,742 @@ impl VScalar for DnsLookup {
     }
 
     fn signatures() -> Vec<ScalarFunctionSignature> {
-        vec![
-            ScalarFunctionSignature::exact(
-                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
-                LogicalTypeHandle::from(LogicalTypeId::Varchar),
-            ),
-            ScalarFunctionSignature::exact(
-                vec![
-                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
-                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
-                ],
-                LogicalTypeHandle::from(LogicalTypeId::Varchar),
-            ),
-        ]
+        vec![ScalarFunctionSignature::exact(
+            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        )]
     }
 }
 
-/// Forward DNS lookup scalar function (returns all records)
+/// Table function for querying TXT DNS records
 ///
-/// Performs DNS lookups to resolve hostnames to all matching IP addresses or DNS records.
+/// Returns all TXT records for a given hostname as a table with one row per record.
 ///
 /// # Arguments
-/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
-/// * `record_type` - Optional VARCHAR specifying the DNS record type (e.g., "A", "MX", "TXT", "NS")
+/// * `hostname` - A VARCHAR containing the hostname to query (e.g., "google.com", "_dmarc.example.com")
 ///
 /// # Returns
-/// * VARCHAR[] - An array of all resolved records:
-///   - Without record_type: All IPv4 addresses
-///   - With record_type: All records of specified type
-///   - Returns NULL on error
-///
-/// # Supported Record Types
-/// A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, CAA
+/// A table with a single column:
+/// * `txt_record` (VARCHAR) - Each TXT record as a separate row
 ///
 /// # Examples
 /// ```sql
-/// -- Get all IPv4 addresses
-/// SELECT dns_lookup_all('google.com') as ips;
-/// -- Returns: [142.251.209.142, ...]
+/// -- Get all TXT records for google.com
+/// SELECT * FROM corey('google.com');
+/// -- Returns a table with multiple rows, one per TXT record
 ///
-/// -- Get all MX records
-/// SELECT dns_lookup_all('google.com', 'MX') as mx_records;
-/// -- Returns: [10 smtp.google.com., ...]
+/// -- Filter TXT records
+/// SELECT * FROM corey('google.com') WHERE txt_record LIKE '%spf%';
 ///
-/// -- Get all TXT records
-/// SELECT dns_lookup_all('google.com', 'TXT') as txt_records;
-/// -- Returns: [v=spf1 include:_spf.google.com ~all, ...]
+/// -- Count TXT records
+/// SELECT COUNT(*) as record_count FROM corey('google.com');
+///
+/// -- Join with other tables
+/// SELECT d.domain, c.txt_record
+/// FROM domains d
+/// CROSS JOIN corey(d.domain_name) c;
 /// ```
-struct DnsLookupAll;
+///
+/// # Notes
+/// - Returns an empty result set if no TXT records are found
+/// - Performs the DNS lookup during the bind phase for efficiency, using the shared
+///   runtime and session resolver (configuration and DNS cache apply)
+/// - All records are fetched at once and then returned in chunks
+struct CoreyBindData {
+    #[allow(dead_code)]
+    hostname: String,
+    txt_records: Vec<String>,
+}
 
-impl VScalar for DnsLookupAll {
-    type State = ();
+struct CoreyInitData {
+    offset: std::sync::atomic::AtomicUsize,
+}
 
-    unsafe fn invoke(
-        _state: &Self::State,
-        input: &mut DataChunkHandle,
-        output: &mut dyn WritableVector,
+struct Corey;
+
+impl VTab for Corey {
+    type InitData = CoreyInitData;
+    type BindData = CoreyBindData;
+
+    fn bind(bind: &BindInfo) -> std::result::Result<Self::BindData, Box<dyn Error>> {
+        // Add result column for TXT records
+        bind.add_result_column("txt_record", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+
+        // Get hostname parameter
+        let hostname = bind.get_parameter(0).to_string();
+
+        // Perform TXT lookup using the shared runtime and resolver, so the
+        // session configuration and DNS cache apply
+        let txt_records = GLOBAL_DNS_STATE.runtime.block_on(async {
+            // Lock-free load of the current resolver
+            let resolver_guard = GLOBAL_DNS_STATE.resolver.load();
+            let hostname = hostname.trim();
+            if hostname.is_empty() {
+                return Ok(Vec::new());
+            }
+            match tracked(&resolver_guard, hostname, RecordType::TXT, resolver_guard.lookup(hostname, RecordType::TXT)).await {
+                Ok(lookup) => {
+                    let records: Vec<String> = lookup
+                        .record_iter()
+                        .map(|record| record.data().to_string())
+                        .collect();
+                    Ok(records)
+                }
+                Err(e) => Err(format!("TXT lookup failed: {}", e)),
+            }
+        })?;
+
+        Ok(CoreyBindData {
+            hostname,
+            txt_records,
+        })
+    }
+
+    fn init(_: &InitInfo) -> std::result::Result<Self::InitData, Box<dyn Error>> {
+        Ok(CoreyInitData {
+            offset: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
     ) -> std::result::Result<(), Box<dyn Error>> {
-        let size = input.len();
-        let hostname_vector = input.flat_vector(0);
-        let mut output_vector = output.list_vector();
+        let bind_data = func.get_bind_data();
+        let init_data = func.get_init_data();
 
-        // Get hostname strings
-        let hostname_values = hostname_vector.as_slice_with_len::<duckdb_string_t>(size);
-        let hostnames: Vec<String> = hostname_values
+        let offset = init_data
+            .offset
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let remaining = bind_data.txt_records.len().saturating_sub(offset);
+
+        if remaining == 0 {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        // Determine how many records to return in this chunk
+        let chunk_size = remaining.min(2048); // DuckDB default vector size
+        output.set_len(chunk_size);
+
+        let result_vector = output.flat_vector(0);
+
+        // Write TXT records to output
+        for (i, txt_record) in bind_data.txt_records[offset..offset + chunk_size]
             .iter()
-            .map(|ptr| DuckString::new(&mut { *ptr }).as_str().to_string())
-            .collect();
+            .enumerate()
+        {
+            result_vector.insert(i, txt_record.as_str());
+        }
 
-        // Check if we have a second parameter (record_type)
-        let record_types: Option<Vec<Option<String>>> = if input.num_columns() > 1 {
-            let record_type_vector = input.flat_vector(1);
-            let record_type_values = record_type_vector.as_slice_with_len::<duckdb_string_t>(size);
-            Some(
-                (0..size)
-                    .map(|i| {
-                        if record_type_vector.row_is_null(i as u64) {
-                            None
-                        } else {
-                            Some(
-                                DuckString::new(&mut { record_type_values[i] })
-                                    .as_str()
-                                    .to_string(),
-                            )
-                        }
-                    })
-                    .collect(),
-            )
-        } else {
-            None
-        };
+        // Update offset for next call
+        init_data
+            .offset
+            .store(offset + chunk_size, std::sync::atomic::Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)])
+    }
+}
+
+/// Table function for discovering subdomains from a list of candidate labels
+///
+/// Resolves `<label>.<domain>` for every candidate label with bounded concurrency
+/// and returns the ones that resolve. If the domain has a DNS wildcard, labels that
+/// only resolve to the wildcard answer are skipped; a failed wildcard probe counts as
+/// no wildcard.
+///
+/// # Arguments
+/// * `domain` - A VARCHAR containing the parent domain (e.g., "example.com")
+/// * `labels` - A VARCHAR[] of candidate labels (e.g., ['www', 'mail', 'api'])
+///
+/// # Returns
+/// A table with the columns:
+/// * `label` (VARCHAR) - The candidate label
+/// * `hostname` (VARCHAR) - The resolved hostname (`<label>.<domain>`)
+/// * `addresses` (VARCHAR[]) - The IPv4 addresses the hostname resolves to
+///
+/// # Examples
+/// ```sql
+/// SELECT * FROM dns_bruteforce('google.com', ['www', 'mail', 'does-not-exist']);
+/// -- Returns rows for www and mail
+/// ```
+///
+/// # Notes
+/// - Lookups use the global concurrency limit (see `set_dns_concurrency_limit`)
+/// - Performs the DNS lookups during the bind phase, like `corey`
+struct DnsBruteforceBindData {
+    rows: Vec<(String, String, Vec<String>)>,
+}
 
-        // Use the global resolver state - load once for all lookups
+struct DnsBruteforceInitData {
+    offset: std::sync::atomic::AtomicUsize,
+}
+
+struct DnsBruteforce;
+
+impl VTab for DnsBruteforce {
+    type InitData = DnsBruteforceInitData;
+    type BindData = DnsBruteforceBindData;
+
+    fn bind(bind: &BindInfo) -> std::result::Result<Self::BindData, Box<dyn Error>> {
+        bind.add_result_column("label", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("hostname", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column(
+            "addresses",
+            LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        );
+
+        let domain = bind.get_parameter(0).to_string().trim().trim_end_matches('.').to_string();
+        let labels = parse_list_parameter(&bind.get_parameter(1));
+
+        // A failed probe (e.g. SERVFAIL from a broken parent zone) counts as no wildcard,
+        // so the labels are still resolved
         let resolver = &GLOBAL_DNS_STATE.resolver;
-        let semaphore = GLOBAL_DNS_STATE.concurrency_semaphore.load();
+        let wildcard = GLOBAL_DNS_STATE
+            .runtime
+            .block_on(dns_wildcard_addresses_async(resolver, &domain))
+            .unwrap_or(None);
 
-        // Process all lookups concurrently with semaphore-controlled execution
-        let futures: Vec<_> = hostnames
-            .iter()
-            .enumerate()
-            .map(|(i, hostname)| {
-                let is_null = hostname_vector.row_is_null(i as u64);
-                let hostname = hostname.clone();
-                let record_type_opt = record_types.as_ref().and_then(|rt| rt[i].clone());
-                let sem = semaphore.clone();
-                async move {
-                    if is_null {
-                        None
-                    } else {
-                        let _permit = sem.acquire().await.unwrap();
-                        let result = if let Some(record_type_str) = record_type_opt {
-                            match parse_record_type(&record_type_str) {
-                                Ok(record_type) => {
-                                    dns_lookup_all_with_type_async(resolver, &hostname, record_type).await
-                                }
-                                Err(e) => Err(e),
-                            }
-                        } else {
-                            dns_lookup_all_async(resolver, &hostname).await
-                        };
-                        result.ok()
-                    }
-                }
+        let inputs: Vec<_> = labels
+            .into_iter()
+            .map(|label| {
+                let hostname = format!("{}.{}", label, domain);
+                Some((label, hostname))
+            })
+            .collect();
+        let results = run_lookups(inputs, |(label, hostname)| async move {
+            let addresses = dns_lookup_all_async(resolver, &hostname, AddressFamily::Ipv4).await.ok()?;
+            Some((label, hostname, addresses))
+        });
+
+        let rows = results
+            .into_iter()
+            .flatten()
+            .filter(|(_, _, addresses)| {
+                // Skip labels that only resolve because of the wildcard
+                let mut sorted = addresses.clone();
+                sorted.sort();
+                wildcard.as_ref() != Some(&sorted)
             })
             .collect();
 
-        let all_results = GLOBAL_DNS_STATE.runtime.block_on(async { futures::future::join_all(futures).await });
+        Ok(DnsBruteforceBindData { rows })
+    }
+
+    fn init(_: &InitInfo) -> std::result::Result<Self::InitData, Box<dyn Error>> {
+        Ok(DnsBruteforceInitData {
+            offset: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
 
-        // Calculate total number of records for capacity
-        let total_capacity: usize = all_results.iter().map(|r| r.as_ref().map_or(0, |v| v.len())).sum();
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let bind_data = func.get_bind_data();
+        let init_data = func.get_init_data();
 
-        // Get the child vector with appropriate capacity
-        let child_vector = output_vector.child(total_capacity);
+        let offset = init_data
+            .offset
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let remaining = bind_data.rows.len().saturating_sub(offset);
 
-        // Now insert the data
-        let mut offset = 0;
-        for (i, result) in all_results.iter().enumerate() {
-            match result {
-                Some(records) => {
-                    output_vector.set_entry(i, offset, records.len());
-                    for record in records {
-                        child_vector.insert(offset, record.as_str());
-                        offset += 1;
-                    }
-                }
-                None => {
-                    output_vector.set_null(i);
-                }
+        if remaining == 0 {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        // Determine how many rows to return in this chunk
+        let chunk_size = remaining.min(2048); // DuckDB default vector size
+        output.set_len(chunk_size);
+
+        let rows = &bind_data.rows[offset..offset + chunk_size];
+        let label_vector = output.flat_vector(0);
+        let hostname_vector = output.flat_vector(1);
+        let mut addresses_vector = output.list_vector(2);
+
+        let total_capacity: usize = rows.iter().map(|(_, _, addresses)| addresses.len()).sum();
+        let address_child = addresses_vector.child(total_capacity);
+
+        let mut child_offset = 0;
+        for (i, (label, hostname, addresses)) in rows.iter().enumerate() {
+            label_vector.insert(i, label.as_str());
+            hostname_vector.insert(i, hostname.as_str());
+            addresses_vector.set_entry(i, child_offset, addresses.len());
+            for address in addresses {
+                address_child.insert(child_offset, address.as_str());
+                child_offset += 1;
             }
         }
+        addresses_vector.set_len(total_capacity);
+
+        // Update offset for next call
+        init_data
+            .offset
+            .store(offset + chunk_size, std::sync::atomic::Ordering::Relaxed);
 
         Ok(())
     }
 
-    fn signatures() -> Vec<ScalarFunctionSignature> {
-        vec![
-            ScalarFunctionSignature::exact(
-                vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
-                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
-            ),
-            ScalarFunctionSignature::exact(
-                vec![
-                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
-                    LogicalTypeHandle::from(LogicalTypeId::Varchar),
-                ],
-                LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
-            ),
-        ]
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar)),
+        ])
     }
 }
 
-/// Configuration update scalar function
+/// Address lookup table function
 ///
-/// Updates the DNS resolver configuration for all subsequent DNS queries.
-/// Supported preset configurations: 'default', 'google', 'cloudflare', 'quad9'
+/// Resolves a hostname's IPv4 and IPv6 addresses and returns one row per address, as an
+/// alternative to unnesting `dns_lookup_all`.
 ///
 /// # Arguments
-/// * `preset` - A VARCHAR containing the configuration preset name
+/// * `hostname` - A VARCHAR containing the hostname to resolve (e.g., "google.com")
 ///
 /// # Returns
-/// * VARCHAR - Success message or error description
+/// A table with the columns:
+/// * `address` (VARCHAR) - A resolved IPv4 or IPv6 address
+/// * `record_type` (VARCHAR) - The record type it came from, `A` or `AAAA`
+///
+/// A hostname without addresses produces zero rows.
 ///
 /// # Examples
 /// ```sql
-/// -- Use Google DNS servers (8.8.8.8, 8.8.4.4)
-/// SELECT set_dns_config('google');
-///
-/// -- Use Cloudflare DNS servers (1.1.1.1, 1.0.0.1)
-/// SELECT set_dns_config('cloudflare');
-///
-/// -- Use Quad9 DNS servers (9.9.9.9, 149.112.112.112)
-/// SELECT set_dns_config('quad9');
-///
-/// -- Use default system configuration
-/// SELECT set_dns_config('default');
+/// SELECT * FROM dns_lookup_table('google.com');
+/// -- Returns one row per A and AAAA record
 /// ```
 ///
-/// # Note
-/// Changing configuration clears the DNS cache.
-struct SetDnsConfig;
+/// # Notes
+/// - Performs the DNS lookups during the bind phase, like `corey`
+/// - Addresses inside the IP filter (see `set_dns_ip_filter`) are skipped
+struct DnsLookupTableBindData {
+    rows: Vec<(String, &'static str)>,
+}
 
-impl VScalar for SetDnsConfig {
-    type State = ();
+struct DnsLookupTableInitData {
+    offset: std::sync::atomic::AtomicUsize,
+}
 
-    unsafe fn invoke(
-        _state: &Self::State,
-        input: &mut DataChunkHandle,
-        output: &mut dyn WritableVector,
-    ) -> std::result::Result<(), Box<dyn Error>> {
-        let size = input.len();
-        let input_vector = input.flat_vector(0);
-        let mut output_vector = output.flat_vector();
+struct DnsLookupTable;
 
-        // Get input strings
-        let values = input_vector.as_slice_with_len::<duckdb_string_t>(size);
+impl VTab for DnsLookupTable {
+    type InitData = DnsLookupTableInitData;
+    type BindData = DnsLookupTableBindData;
 
-        for i in 0..size {
-            if input_vector.row_is_null(i as u64) {
-                output_vector.set_null(i);
-                continue;
-            }
+    fn bind(bind: &BindInfo) -> std::result::Result<Self::BindData, Box<dyn Error>> {
+        bind.add_result_column("address", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("record_type", LogicalTypeHandle::from(LogicalTypeId::Varchar));
 
-            let preset = DuckString::new(&mut { values[i] }).as_str().trim().to_lowercase();
+        let hostname = bind.get_parameter(0).to_string();
 
-            // Determine which configuration to use
-            let config = match preset.as_str() {
-                "default" => ResolverConfig::default(),
-                "google" => ResolverConfig::google(),
-                "cloudflare" => ResolverConfig::cloudflare(),
-                "quad9" => ResolverConfig::quad9(),
-                _ => {
-                    let error_msg = format!("Unknown preset '{}'. Supported presets: default, google, cloudflare, quad9", preset);
-                    output_vector.insert(i, &error_msg);
-                    continue;
-                }
-            };
+        if hostname.trim().is_empty() {
+            return Ok(DnsLookupTableBindData { rows: Vec::new() });
+        }
 
-            // Update the global resolver configuration
-            // This is a lock-free atomic operation - extremely fast!
-            match GLOBAL_DNS_STATE.update_config(config) {
-                Ok(_) => {
-                    let success_msg = format!("DNS configuration updated to '{}'", preset);
-                    output_vector.insert(i, &success_msg);
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to update DNS configuration: {}", e);
-                    output_vector.insert(i, &error_msg);
-                }
-            }
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let (ipv4, ipv6) = GLOBAL_DNS_STATE.runtime.block_on(async {
+            futures::join!(
+                dns_lookup_all_async(resolver, &hostname, AddressFamily::Ipv4),
+                dns_lookup_aaaa_all_async(resolver, &hostname),
+            )
+        });
+
+        // Missing records or failed lookups of either family contribute no rows
+        let rows = ipv4
+            .unwrap_or_default()
+            .into_iter()
+            .map(|address| (address, "A"))
+            .chain(ipv6.unwrap_or_default().into_iter().map(|address| (address, "AAAA")))
+            .collect();
+
+        Ok(DnsLookupTableBindData { rows })
+    }
+
+    fn init(_: &InitInfo) -> std::result::Result<Self::InitData, Box<dyn Error>> {
+        Ok(DnsLookupTableInitData {
+            offset: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let bind_data = func.get_bind_data();
+        let init_data = func.get_init_data();
+
+        let offset = init_data
+            .offset
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let remaining = bind_data.rows.len().saturating_sub(offset);
+
+        if remaining == 0 {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        // Determine how many rows to return in this chunk
+        let chunk_size = remaining.min(2048); // DuckDB default vector size
+        output.set_len(chunk_size);
+
+        let address_vector = output.flat_vector(0);
+        let record_type_vector = output.flat_vector(1);
+        for (i, (address, record_type)) in bind_data.rows[offset..offset + chunk_size].iter().enumerate() {
+            address_vector.insert(i, address.as_str());
+            record_type_vector.insert(i, *record_type);
         }
 
+        // Update offset for next call
+        init_data
+            .offset
+            .store(offset + chunk_size, std::sync::atomic::Ordering::Relaxed);
+
         Ok(())
     }
 
-    fn signatures() -> Vec<ScalarFunctionSignature> {
-        vec![ScalarFunctionSignature::exact(
-            vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)],
-            LogicalTypeHandle::from(LogicalTypeId::Varchar),
-        )]
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)])
     }
 }
 
-/// Concurrency limit configuration scalar function
+/// Hostname-to-address table function
 ///
-/// Updates the concurrency limit for DNS lookup operations to prevent TCP connection exhaustion.
+/// Resolves the IPv4 addresses of a list of hostnames and returns one
+/// `(hostname, address)` row per address, keeping the input hostname next to every
+/// result. A column is passed as a list, e.g. `(SELECT list(host) FROM domains)`.
 ///
 /// # Arguments
-/// * `limit` - A BIGINT specifying the maximum number of concurrent DNS requests (must be > 0)
+/// * `hostnames` - A VARCHAR[] of hostnames to resolve (e.g., ['google.com']); NULL and
+///   empty elements are skipped
 ///
 /// # Returns
-/// * VARCHAR - Success message or error description
+/// A table with the columns:
+/// * `hostname` (VARCHAR) - The hostname as given
+/// * `address` (VARCHAR) - A resolved IPv4 address
+///
+/// A hostname without addresses, or whose lookup fails, produces a single row with a
+/// NULL address so the hostname is not lost.
 ///
 /// # Examples
 /// ```sql
-/// -- Set concurrency limit to 100
-/// SELECT set_dns_concurrency_limit(100);
-/// -- Returns: Concurrency limit updated to 100
-///
-/// -- Set concurrency limit to 500 for high-throughput scenarios
-/// SELECT set_dns_concurrency_limit(500);
-/// -- Returns: Concurrency limit updated to 500
-///
-/// -- Reset to default (50)
-/// SELECT set_dns_concurrency_limit(50);
-/// -- Returns: Concurrency limit updated to 50
+/// SELECT * FROM dns_lookup_all_table(['google.com']);
+/// -- Returns one row per IPv4 address
 ///
-/// -- Invalid limit returns error
-/// SELECT set_dns_concurrency_limit(0);
-/// -- Returns: Concurrency limit must be greater than 0
+/// SELECT * FROM dns_lookup_all_table((SELECT list(host) FROM domains));
 /// ```
 ///
-/// # Note
-/// This setting applies globally to all DNS lookup operations and takes effect immediately.
-struct SetConcurrencyLimit;
+/// # Notes
+/// - Lookups use the global concurrency limit (see `set_dns_concurrency_limit`)
+/// - Performs the DNS lookups during the bind phase, like `corey`
+struct DnsLookupAllTableBindData {
+    rows: Vec<(String, Option<String>)>,
+}
 
-impl VScalar for SetConcurrencyLimit {
-    type State = ();
+struct DnsLookupAllTableInitData {
+    offset: std::sync::atomic::AtomicUsize,
+}
 
-    unsafe fn invoke(
-        _state: &Self::State,
-        input: &mut DataChunkHandle,
-        output: &mut dyn WritableVector,
-    ) -> std::result::Result<(), Box<dyn Error>> {
-        let size = input.len();
-        let input_vector = input.flat_vector(0);
-        let mut output_vector = output.flat_vector();
+struct DnsLookupAllTable;
 
-        // Get input values
-        let values = input_vector.as_slice_with_len::<i64>(size);
+impl VTab for DnsLookupAllTable {
+    type InitData = DnsLookupAllTableInitData;
+    type BindData = DnsLookupAllTableBindData;
 
-        for i in 0..size {
-            if input_vector.row_is_null(i as u64) {
-                output_vector.set_null(i);
-                continue;
-            }
+    fn bind(bind: &BindInfo) -> std::result::Result<Self::BindData, Box<dyn Error>> {
+        bind.add_result_column("hostname", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("address", LogicalTypeHandle::from(LogicalTypeId::Varchar));
 
-            let limit = values[i];
+        let hostnames = parse_list_parameter(&bind.get_parameter(0));
 
-            if limit <= 0 {
-                let error_msg = "Concurrency limit must be greater than 0";
-                output_vector.insert(i, error_msg);
-                continue;
-            }
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = hostnames.iter().map(|hostname| Some(hostname.clone())).collect();
+        let results = run_lookups(inputs, |hostname| async move {
+            dns_lookup_all_async(resolver, &hostname, AddressFamily::Ipv4).await.ok()
+        });
+
+        let rows = hostnames
+            .into_iter()
+            .zip(results)
+            .flat_map(|(hostname, addresses)| match addresses.unwrap_or_default() {
+                addresses if addresses.is_empty() => vec![(hostname, None)],
+                addresses => addresses.into_iter().map(|address| (hostname.clone(), Some(address))).collect(),
+            })
+            .collect();
+
+        Ok(DnsLookupAllTableBindData { rows })
+    }
+
+    fn init(_: &InitInfo) -> std::result::Result<Self::InitData, Box<dyn Error>> {
+        Ok(DnsLookupAllTableInitData {
+            offset: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let bind_data = func.get_bind_data();
+        let init_data = func.get_init_data();
+
+        let offset = init_data
+            .offset
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let remaining = bind_data.rows.len().saturating_sub(offset);
+
+        if remaining == 0 {
+            output.set_len(0);
+            return Ok(());
+        }
 
-            // Update the global concurrency limit
-            // This is a lock-free atomic operation - extremely fast!
-            match GLOBAL_DNS_STATE.set_dns_concurrency_limit(limit as usize) {
-                Ok(_) => {
-                    let success_msg = format!("Concurrency limit updated to {}", limit);
-                    output_vector.insert(i, &success_msg);
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to update concurrency limit: {}", e);
-                    output_vector.insert(i, &error_msg);
-                }
+        // Determine how many rows to return in this chunk
+        let chunk_size = remaining.min(2048); // DuckDB default vector size
+        output.set_len(chunk_size);
+
+        let hostname_vector = output.flat_vector(0);
+        let mut address_vector = output.flat_vector(1);
+        for (i, (hostname, address)) in bind_data.rows[offset..offset + chunk_size].iter().enumerate() {
+            hostname_vector.insert(i, hostname.as_str());
+            match address {
+                Some(address) => address_vector.insert(i, address.as_str()),
+                None => address_vector.set_null(i),
             }
         }
 
+        // Update offset for next call
+        init_data
+            .offset
+            .store(offset + chunk_size, std::sync::atomic::Ordering::Relaxed);
+
         Ok(())
     }
 
-    fn signatures() -> Vec<ScalarFunctionSignature> {
-        vec![ScalarFunctionSignature::exact(
-            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
-            LogicalTypeHandle::from(LogicalTypeId::Varchar),
-        )]
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![LogicalTypeHandle::list(&LogicalTypeHandle::from(LogicalTypeId::Varchar))])
     }
 }
 
-/// Cache size configuration scalar function
+/// Table function for reverse DNS lookups of every host address in a network
 ///
-/// Updates the DNS cache size for the resolver. The cache stores DNS query results
-/// to improve performance by avoiding repeated lookups for the same queries.
+/// Enumerates the host addresses of a CIDR range and resolves their PTR records with
+/// bounded concurrency, e.g. to inventory the hostnames of a subnet.
 ///
 /// # Arguments
-/// * `size` - A BIGINT specifying the maximum number of cached DNS queries (must be > 0)
+/// * `cidr` - A VARCHAR containing an IPv4 or IPv6 network (e.g., "192.0.2.0/28")
+/// * `min_prefix` - Optional named INTEGER: the smallest IPv4 prefix length accepted
+///   (0-32, default 16); IPv6 networks may hold as many addresses as such an IPv4 network
 ///
 /// # Returns
-/// * VARCHAR - Success message or error description
+/// A table with the columns:
+/// * `ip` (VARCHAR) - The host address
+/// * `hostname` (VARCHAR) - A PTR hostname of the address, one row per PTR record
+///
+/// Addresses without PTR records produce no rows.
 ///
 /// # Examples
 /// ```sql
-/// -- Set cache size to 4096 (default)
-/// SELECT set_dns_cache_size(4096);
-/// -- Returns: DNS cache size updated to 4096
+/// SELECT * FROM reverse_dns_range('8.8.8.0/28');
+/// -- Returns: 8.8.8.8 | dns.google
 ///
-/// -- Set cache size to 8192 for larger workloads
-/// SELECT set_dns_cache_size(8192);
-/// -- Returns: DNS cache size updated to 8192
+/// SELECT * FROM reverse_dns_range('10.0.0.0/12', min_prefix := 12);
 /// ```
 ///
-/// # Note
-/// Changing the cache size rebuilds the resolver and clears the existing cache.
-/// This operation takes effect immediately for all subsequent DNS queries.
-struct SetDnsCacheSize;
+/// # Notes
+/// - Lookups use the global concurrency limit (see `set_dns_concurrency_limit`)
+/// - Performs the DNS lookups during the bind phase, like `corey`
+struct ReverseDnsRangeBindData {
+    rows: Vec<(String, String)>,
+}
 
-impl VScalar for SetDnsCacheSize {
-    type State = ();
+struct ReverseDnsRangeInitData {
+    offset: std::sync::atomic::AtomicUsize,
+}
 
-    unsafe fn invoke(
-        _state: &Self::State,
-        input: &mut DataChunkHandle,
-        output: &mut dyn WritableVector,
-    ) -> std::result::Result<(), Box<dyn Error>> {
-        let size = input.len();
-        let input_vector = input.flat_vector(0);
-        let mut output_vector = output.flat_vector();
+struct ReverseDnsRange;
 
-        // Get input values
-        let values = input_vector.as_slice_with_len::<i64>(size);
+impl VTab for ReverseDnsRange {
+    type InitData = ReverseDnsRangeInitData;
+    type BindData = ReverseDnsRangeBindData;
 
-        for i in 0..size {
-            if input_vector.row_is_null(i as u64) {
-                output_vector.set_null(i);
-                continue;
-            }
+    fn bind(bind: &BindInfo) -> std::result::Result<Self::BindData, Box<dyn Error>> {
+        bind.add_result_column("ip", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("hostname", LogicalTypeHandle::from(LogicalTypeId::Varchar));
 
-            let cache_size = values[i];
+        let min_prefix = bind
+            .get_named_parameter("min_prefix")
+            .map_or(REVERSE_RANGE_MIN_PREFIX, |value| value.to_int64());
+        if !(0..=32).contains(&min_prefix) {
+            return Err(format!("min_prefix must be between 0 and 32, got {}", min_prefix).into());
+        }
+        let cidr = parse_cidr(&bind.get_parameter(0).to_string())?;
+        let addresses = cidr_host_addresses(&cidr, min_prefix as u8)?;
 
-            if cache_size <= 0 {
-                let error_msg = "Cache size must be greater than 0";
-                output_vector.insert(i, error_msg);
-                continue;
-            }
+        let resolver = &GLOBAL_DNS_STATE.resolver;
+        let inputs: Vec<_> = addresses.into_iter().map(|addr| Some(addr.to_string())).collect();
+        let results = run_lookups(inputs, |ip| async move {
+            let hostnames = reverse_dns_lookup_all_async(resolver, &ip).await.ok()?;
+            Some((ip, hostnames))
+        });
 
-            // Update the global cache size
-            // This is a lock-free atomic operation - extremely fast!
-            match GLOBAL_DNS_STATE.set_dns_cache_size(cache_size as usize) {
-                Ok(_) => {
-                    let success_msg = format!("DNS cache size updated to {}", cache_size);
-                    output_vector.insert(i, &success_msg);
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to update cache size: {}", e);
-                    output_vector.insert(i, &error_msg);
-                }
-            }
+        let rows = results
+            .into_iter()
+            .flatten()
+            .flat_map(|(ip, hostnames)| hostnames.into_iter().map(move |hostname| (ip.clone(), hostname)))
+            .collect();
+
+        Ok(ReverseDnsRangeBindData { rows })
+    }
+
+    fn init(_: &InitInfo) -> std::result::Result<Self::InitData, Box<dyn Error>> {
+        Ok(ReverseDnsRangeInitData {
+            offset: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    fn func(
+        func: &TableFunctionInfo<Self>,
+        output: &mut DataChunkHandle,
+    ) -> std::result::Result<(), Box<dyn Error>> {
+        let bind_data = func.get_bind_data();
+        let init_data = func.get_init_data();
+
+        let offset = init_data
+            .offset
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let remaining = bind_data.rows.len().saturating_sub(offset);
+
+        if remaining == 0 {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        // Determine how many rows to return in this chunk
+        let chunk_size = remaining.min(2048); // DuckDB default vector size
+        output.set_len(chunk_size);
+
+        let ip_vector = output.flat_vector(0);
+        let hostname_vector = output.flat_vector(1);
+        for (i, (ip, hostname)) in bind_data.rows[offset..offset + chunk_size].iter().enumerate() {
+            ip_vector.insert(i, ip.as_str());
+            hostname_vector.insert(i, hostname.as_str());
         }
 
+        // Update offset for next call
+        init_data
+            .offset
+            .store(offset + chunk_size, std::sync::atomic::Ordering::Relaxed);
+
         Ok(())
     }
 
-    fn signatures() -> Vec<ScalarFunctionSignature> {
-        vec![ScalarFunctionSignature::exact(
-            vec![LogicalTypeHandle::from(LogicalTypeId::Bigint)],
-            LogicalTypeHandle::from(LogicalTypeId::Varchar),
-        )]
+    fn parameters() -> Option<Vec<LogicalTypeHandle>> {
+        Some(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalTypeHandle)>> {
+        Some(vec![(
+            "min_prefix".to_string(),
+            LogicalTypeHandle::from(LogicalTypeId::Integer),
+        )])
     }
 }
 
-/// Table function for querying TXT DNS records
+/// A resource record of an answer, as returned by `dns_query_records`
+struct QueryRecord {
+    name: String,
+    record_type: String,
+    ttl: u32,
+    class: String,
+    rdata: String,
+}
+
+/// Table function for querying the full resource records of an answer
 ///
-/// Returns all TXT records for a given hostname as a table with one row per record.
+/// Returns every record of the answer with the metadata the scalar functions hide,
+/// including the CNAME records leading to the answer, for debugging DNS.
 ///
 /// # Arguments
-/// * `hostname` - A VARCHAR containing the hostname to query (e.g., "google.com", "_dmarc.example.com")
+/// * `name` - A VARCHAR containing the name to query (e.g., "www.github.com")
+/// * `rtype` - A VARCHAR specifying the DNS record type (case-insensitive)
 ///
 /// # Returns
-/// A table with a single column:
-/// * `txt_record` (VARCHAR) - Each TXT record as a separate row
+/// A table with the columns:
+/// * `name` (VARCHAR) - The owner name of the record, without trailing dot
+/// * `record_type` (VARCHAR) - The record type, e.g. "CNAME" or "A"
+/// * `ttl` (UINTEGER) - The remaining TTL in seconds
+/// * `class` (VARCHAR) - The record class, e.g. "IN"
+/// * `rdata` (VARCHAR) - The record data in its canonical text form
+///
+/// A name without records of the type produces no rows. Records are not subject to
+/// the IP filter.
 ///
 /// # Examples
 /// ```sql
-/// -- Get all TXT records for google.com
-/// SELECT * FROM corey('google.com');
-/// -- Returns a table with multiple rows, one per TXT record
-///
-/// -- Filter TXT records
-/// SELECT * FROM corey('google.com') WHERE txt_record LIKE '%spf%';
-///
-/// -- Count TXT records
-/// SELECT COUNT(*) as record_count FROM corey('google.com');
-///
-/// -- Join with other tables
-/// SELECT d.domain, c.txt_record
-/// FROM domains d
-/// CROSS JOIN corey(d.domain_name) c;
+/// SELECT * FROM dns_query_records('www.github.com', 'A');
+/// -- Returns: www.github.com | CNAME | 3600 | IN | github.com.
+/// --          github.com     | A     | 60   | IN | 140.82.121.4
 /// ```
 ///
 /// # Notes
-/// - Returns an empty result set if no TXT records are found
-/// - Performs the DNS lookup during the bind phase for efficiency
-/// - All records are fetched at once and then returned in chunks
-struct CoreyBindData {
-    #[allow(dead_code)]
-    hostname: String,
-    txt_records: Vec<String>,
-    #[allow(dead_code)]
-    resolver_state: DnsResolverState,
+/// - Performs the DNS lookup during the bind phase, like `corey`
+/// - An unsupported record type or a failed lookup raises an error
+struct DnsQueryRecordsBindData {
+    records: Vec<QueryRecord>,
 }
 
-struct CoreyInitData {
+struct DnsQueryRecordsInitData {
     offset: std::sync::atomic::AtomicUsize,
 }
 
-struct Corey;
+struct DnsQueryRecords;
 
-impl VTab for Corey {
-    type InitData = CoreyInitData;
-    type BindData = CoreyBindData;
+impl VTab for DnsQueryRecords {
+    type InitData = DnsQueryRecordsInitData;
+    type BindData = DnsQueryRecordsBindData;
 
     fn bind(bind: &BindInfo) -> std::result::Result<Self::BindData, Box<dyn Error>> {
-        // Add result column for TXT records
-        bind.add_result_column("txt_record", LogicalTypeHandle::from(LogicalTypeId::Varchar));
-
-        // Get hostname parameter
-        let hostname = bind.get_parameter(0).to_string();
+        bind.add_result_column("name", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("record_type", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("ttl", LogicalTypeHandle::from(LogicalTypeId::UInteger));
+        bind.add_result_column("class", LogicalTypeHandle::from(LogicalTypeId::Varchar));
+        bind.add_result_column("rdata", LogicalTypeHandle::from(LogicalTypeId::Varchar));
 
-        // Create a long-lived resolver state
-        let resolver_state = DnsResolverState::new()?;
+        let name = bind.get_parameter(0).to_string();
+        let record_type = parse_record_type(&bind.get_parameter(1).to_string())?;
 
-        // Perform TXT lookup using the resolver state
-        let txt_records = resolver_state.runtime.block_on(async {
+        let records = GLOBAL_DNS_STATE.runtime.block_on(async {
+            let name = to_ascii_hostname(&name)?;
+            if name.is_empty() {
+                return Ok::<_, Box<dyn Error>>(Vec::new());
+            }
             // Lock-free load of the current resolver
-            let resolver_guard = resolver_state.resolver.load();
-            match resolver_guard.lookup(hostname.trim(), RecordType::TXT).await {
-                Ok(lookup) => {
-                    let records: Vec<String> = lookup
-                        .record_iter()
-                        .map(|record| record.data().to_string())
-                        .collect();
-                    Ok(records)
-                }
-                Err(e) => Err(format!("TXT lookup failed: {}", e)),
+            let resolver_guard = GLOBAL_DNS_STATE.resolver.load();
+            match tracked(&resolver_guard, &name, record_type, resolver_guard.lookup(name.as_str(), record_type)).await {
+                Ok(lookup) => Ok(lookup
+                    .record_iter()
+                    .map(|record| QueryRecord {
+                        name: answer_name(record.name()),
+                        record_type: record.record_type().to_string(),
+                        ttl: record.ttl(),
+                        class: record.dns_class().to_string(),
+                        rdata: format_record_data(record.data()),
+                    })
+                    .collect()),
+                Err(e) if is_no_records_error(&e) => Ok(Vec::new()),
+                Err(e) => Err(format!("DNS lookup failed: {}", e).into()),
             }
         })?;
 
-        Ok(CoreyBindData {
-            hostname,
-            txt_records,
-            resolver_state,
-        })
+        Ok(DnsQueryRecordsBindData { records })
     }
 
     fn init(_: &InitInfo) -> std::result::Result<Self::InitData, Box<dyn Error>> {
-        Ok(CoreyInitData {
+        Ok(DnsQueryRecordsInitData {
             offset: std::sync::atomic::AtomicUsize::new(0),
         })
     }
@@ -1101,25 +10391,28 @@ impl VTab for Corey {
         let offset = init_data
             .offset
             .load(std::sync::atomic::Ordering::Relaxed);
-        let remaining = bind_data.txt_records.len().saturating_sub(offset);
+        let remaining = bind_data.records.len().saturating_sub(offset);
 
         if remaining == 0 {
             output.set_len(0);
             return Ok(());
         }
 
-        // Determine how many records to return in this chunk
+        // Determine how many rows to return in this chunk
         let chunk_size = remaining.min(2048); // DuckDB default vector size
         output.set_len(chunk_size);
 
-        let result_vector = output.flat_vector(0);
-
-        // Write TXT records to output
-        for (i, txt_record) in bind_data.txt_records[offset..offset + chunk_size]
-            .iter()
-            .enumerate()
-        {
-            result_vector.insert(i, txt_record.as_str());
+        let name_vector = output.flat_vector(0);
+        let record_type_vector = output.flat_vector(1);
+        let mut ttl_vector = output.flat_vector(2);
+        let class_vector = output.flat_vector(3);
+        let rdata_vector = output.flat_vector(4);
+        for (i, record) in bind_data.records[offset..offset + chunk_size].iter().enumerate() {
+            name_vector.insert(i, record.name.as_str());
+            record_type_vector.insert(i, record.record_type.as_str());
+            ttl_vector.as_mut_slice::<u32>()[i] = record.ttl;
+            class_vector.insert(i, record.class.as_str());
+            rdata_vector.insert(i, record.rdata.as_str());
         }
 
         // Update offset for next call
@@ -1131,18 +10424,313 @@ impl VTab for Corey {
     }
 
     fn parameters() -> Option<Vec<LogicalTypeHandle>> {
-        Some(vec![LogicalTypeHandle::from(LogicalTypeId::Varchar)])
+        Some(vec![
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+            LogicalTypeHandle::from(LogicalTypeId::Varchar),
+        ])
+    }
+}
+
+/// Logger writing the extension's log records to stderr
+///
+/// DuckDB installs no `log` backend for extensions, so the extension brings its own.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Enables logging at the level given by the `RUST_LOG` environment variable
+///
+/// Accepts a bare level (`debug`) or a level for this crate (`dns=debug`) among
+/// comma-separated directives; directives for other crates are ignored. Without a
+/// matching directive, logging stays off and log statements are no-ops.
+fn init_logging() {
+    let Ok(directives) = std::env::var("RUST_LOG") else {
+        return;
+    };
+    let level = directives.split(',').map(str::trim).find_map(|directive| match directive.split_once('=') {
+        Some((target, level)) if target.trim() == env!("CARGO_CRATE_NAME") => {
+            log::LevelFilter::from_str(level.trim()).ok()
+        }
+        Some(_) => None,
+        None => log::LevelFilter::from_str(directive).ok(),
+    });
+    if let Some(level) = level {
+        if log::set_logger(&StderrLogger).is_ok() {
+            log::set_max_level(level);
+        }
     }
 }
 
+/// Extension entry point, registers all functions of the extension
+///
+/// # Safety
+/// Called by DuckDB when the extension is loaded, with a valid connection.
 #[duckdb_entrypoint_c_api()]
 pub unsafe fn extension_entrypoint(con: Connection) -> Result<(), Box<dyn Error>> {
+    init_logging();
+
     con.register_scalar_function::<ReverseDnsLookup>("reverse_dns_lookup")?;
+    con.register_scalar_function::<ReverseDnsLookupAll>("reverse_dns_lookup_all")?;
     con.register_scalar_function::<DnsLookup>("dns_lookup")?;
     con.register_scalar_function::<DnsLookupAll>("dns_lookup_all")?;
+    con.register_scalar_function::<DnsLookupAaaa>("dns_lookup_aaaa")?;
+    con.register_scalar_function::<DnsLookupAaaaAll>("dns_lookup_aaaa_all")?;
+    con.register_scalar_function::<DnsQuery>("dns_query")?;
+    con.register_scalar_function::<DnsLookupAny>("dns_lookup_any")?;
+    con.register_scalar_function::<DnsLookupCname>("dns_lookup_cname")?;
+    con.register_scalar_function::<DnsLookupMx>("dns_lookup_mx")?;
+    con.register_scalar_function::<DnsLookupMxHosts>("dns_lookup_mx_hosts")?;
+    con.register_scalar_function::<DnsLookupTxt>("dns_lookup_txt")?;
+    con.register_scalar_function::<DnsLookupWithStatus>("dns_lookup_with_status")?;
+    con.register_scalar_function::<DnsLookupAllWithStatus>("dns_lookup_all_with_status")?;
+    con.register_scalar_function::<DnsLookupSrv>("dns_lookup_srv")?;
+    con.register_scalar_function::<DnsLookupDoh>("dns_lookup_doh")?;
+    con.register_scalar_function::<DnsLookupDot>("dns_lookup_dot")?;
+    con.register_scalar_function::<DnsLookupNs>("dns_lookup_ns")?;
+    con.register_scalar_function::<DnsLookupSoa>("dns_lookup_soa")?;
+    con.register_scalar_function::<DnsDomainAdmin>("dns_domain_admin")?;
+    con.register_scalar_function::<DnsLookupCaa>("dns_lookup_caa")?;
+    con.register_scalar_function::<DnsLookupTlsa>("dns_lookup_tlsa")?;
+    con.register_scalar_function::<DnsCheckBlocklist>("dns_check_blocklist")?;
+    con.register_scalar_function::<DnsResolves>("dns_resolves")?;
+    con.register_scalar_function::<DnsFcrdns>("dns_fcrdns")?;
+    con.register_scalar_function::<DnsLookupAsn>("dns_lookup_asn")?;
+    con.register_scalar_function::<DnsIsWildcard>("dns_is_wildcard")?;
+    con.register_scalar_function::<DnsLookupAddrs>("dns_lookup_addrs")?;
+    con.register_scalar_function::<DnsLookupMap>("dns_lookup_map")?;
+    con.register_scalar_function::<DnsLookupDetailed>("dns_lookup_detailed")?;
+    con.register_scalar_function::<DnsLookupResolvedFqdn>("dns_lookup_resolved_fqdn")?;
+    con.register_scalar_function::<DnsLookupSpf>("dns_lookup_spf")?;
+    con.register_scalar_function::<DnsSpfIncludes>("dns_spf_includes")?;
+    con.register_scalar_function::<DnsLookupDmarc>("dns_lookup_dmarc")?;
+    con.register_scalar_function::<DnsDmarcPolicy>("dns_dmarc_policy")?;
+    con.register_scalar_function::<DnsLookupSecure>("dns_lookup_secure")?;
+    con.register_scalar_function::<DnsLookupDebug>("dns_lookup_debug")?;
+    con.register_scalar_function::<DnsTxtContains>("dns_txt_contains")?;
+    con.register_scalar_function::<DnsRcode>("dns_rcode")?;
+    con.register_scalar_function::<DnsLookupPrefer>("dns_lookup_prefer")?;
+    con.register_scalar_function::<DnsRecordCounts>("dns_record_counts")?;
+    con.register_scalar_function::<DnsApexCnameMisconfig>("dns_apex_cname_misconfig")?;
+    con.register_scalar_function::<DnsHappyEyeballs>("dns_happy_eyeballs")?;
+    con.register_scalar_function::<DnsLookupFastest>("dns_lookup_fastest")?;
+    con.register_scalar_function::<DnsFastFlux>("dns_fast_flux")?;
+    con.register_scalar_function::<DnsTxtDiff>("dns_txt_diff")?;
+    con.register_scalar_function::<DnsTxtLongest>("dns_txt_longest")?;
+    con.register_scalar_function::<DnsDualStackConsistent>("dns_dual_stack_consistent")?;
+    con.register_scalar_function::<DnsDig>("dns_dig")?;
+    con.register_scalar_function::<DnsFingerprint>("dns_fingerprint")?;
+    con.register_scalar_function::<DnsCacheStats>("dns_cache_stats")?;
+    con.register_scalar_function::<DnsCacheClear>("dns_cache_clear")?;
+    con.register_scalar_function::<DnsResolverCacheStats>("dns_resolver_cache_stats")?;
+    con.register_scalar_function::<DnsTldType>("dns_tld_type")?;
     con.register_scalar_function::<SetDnsConfig>("set_dns_config")?;
+    con.register_scalar_function::<SetDnsProtocol>("set_dns_protocol")?;
+    con.register_scalar_function::<SetDnsIpv6ZoneId>("set_dns_ipv6_zone_id")?;
+    con.register_scalar_function::<SetDnsIpFilter>("set_dns_ip_filter")?;
+    con.register_scalar_function::<SetDnsSearchOrder>("set_dns_search_order")?;
+    con.register_scalar_function::<SetDnsHosts>("set_dns_hosts")?;
     con.register_scalar_function::<SetConcurrencyLimit>("set_dns_concurrency_limit")?;
     con.register_scalar_function::<SetDnsCacheSize>("set_dns_cache_size")?;
+    con.register_scalar_function::<SetDnsNegativeCacheTtl>("set_dns_negative_cache_ttl")?;
+    con.register_scalar_function::<SetDnsTimeout>("set_dns_timeout")?;
+    con.register_scalar_function::<SetDnsAttempts>("set_dns_attempts")?;
+    con.register_scalar_function::<SetDnsResolverCacheSize>("set_dns_resolver_cache_size")?;
+    con.register_scalar_function::<SetDnsAddressFamily>("set_dns_address_family")?;
     con.register_table_function::<Corey>("corey")?;
+    con.register_table_function::<DnsBruteforce>("dns_bruteforce")?;
+    con.register_table_function::<DnsLookupTable>("dns_lookup_table")?;
+    con.register_table_function::<DnsLookupAllTable>("dns_lookup_all_table")?;
+    con.register_table_function::<ReverseDnsRange>("reverse_dns_range")?;
+    con.register_table_function::<DnsQueryRecords>("dns_query_records")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_tld_uses_the_root_zone_list() {
+        assert_eq!(classify_tld("www.google.com"), Some("gtld"));
+        assert_eq!(classify_tld("www.bbc.co.uk"), Some("cctld"));
+        assert_eq!(classify_tld("Web.DEV."), Some("new_gtld"));
+        assert_eq!(classify_tld("localhost"), Some("special"));
+        assert_eq!(classify_tld("example.notarealtld"), None);
+        assert_eq!(classify_tld("example.zz"), None);
+    }
+
+    #[test]
+    fn classify_tld_converts_idn_tlds_to_punycode() {
+        assert_eq!(classify_tld("пример.рф"), Some("cctld"));
+        assert_eq!(classify_tld("example.xn--p1ai"), Some("cctld"));
+        assert_eq!(classify_tld("例子.中国"), Some("cctld"));
+        assert_eq!(classify_tld("example.みんな"), Some("new_gtld"));
+    }
+
+    #[test]
+    fn parse_resolver_spec_rejects_out_of_range_timeouts() {
+        let error = parse_resolver_spec("1.1.1.1;timeout=1e30").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid resolver spec '1.1.1.1;timeout=1e30': timeout must be at most 3600 seconds, got '1e30'"
+        );
+        assert!(parse_resolver_spec("1.1.1.1;timeout=inf").is_err());
+        let (_, opts) = parse_resolver_spec("1.1.1.1;timeout=3600").unwrap();
+        assert_eq!(opts.timeout, Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn parse_doh_version_defaults_to_http2() {
+        assert_eq!(parse_doh_version("h2").unwrap(), Protocol::Https);
+        assert_eq!(parse_doh_version(" H2 ").unwrap(), Protocol::Https);
+        #[cfg(feature = "doh3")]
+        assert_eq!(parse_doh_version("h3").unwrap(), Protocol::H3);
+        #[cfg(not(feature = "doh3"))]
+        assert!(parse_doh_version("h3").is_err());
+        assert_eq!(
+            parse_doh_version("h1").unwrap_err().to_string(),
+            "Unknown DoH HTTP version 'h1'. Supported versions: h2, h3"
+        );
+    }
+
+    #[test]
+    fn nameserver_list_spec_keeps_the_query_order() {
+        let servers = ["192.0.2.1".to_string(), "8.8.8.8".to_string(), "192.0.2.1:53".to_string()];
+        let (spec, key) = nameserver_list_spec(&servers).unwrap();
+        assert_eq!(spec, "192.0.2.1:53,8.8.8.8:53");
+        assert_eq!(key, "list:8.8.8.8:53,192.0.2.1:53");
+
+        let (_, reordered_key) = nameserver_list_spec(&["8.8.8.8".to_string(), "192.0.2.1".to_string()]).unwrap();
+        assert_eq!(reordered_key, key);
+    }
+
+    #[test]
+    fn run_lookups_respects_the_concurrency_limit() {
+        GLOBAL_DNS_STATE.set_dns_concurrency_limit(3).unwrap();
+        PEAK_IN_FLIGHT.store(0, Ordering::SeqCst);
+        let results = run_lookups((0..20).map(Some).collect(), |i| async move {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            Some(i)
+        });
+        let peak = PEAK_IN_FLIGHT.load(Ordering::SeqCst);
+        GLOBAL_DNS_STATE.set_dns_concurrency_limit(50).unwrap();
+
+        assert_eq!(results, (0..20).map(Some).collect::<Vec<_>>());
+        assert!(peak <= 3, "peak in-flight lookups {} exceeded the limit of 3", peak);
+        assert!(peak > 0);
+    }
+
+    #[test]
+    fn name_to_text_decodes_and_escapes_labels() {
+        let name = |labels: Vec<&[u8]>| Name::from_labels(labels).unwrap();
+        assert_eq!(name_to_text(&name(vec!["bücher".as_bytes(), b"example", b"com"])), "bücher.example.com");
+        assert_eq!(name_to_text(&name(vec![b"xn--bcher-kva", b"example", b"com"])), "bücher.example.com");
+        assert_eq!(name_to_text(&name(vec![b"\xffab", b"example"])), "\\255ab.example");
+        assert_eq!(name_to_text(&name(vec![b"a\x07b", b"example"])), "a\\007b.example");
+        assert_eq!(name_to_text(&name(vec![b"_a.b", b"example"])), "_a\\.b.example");
+        assert_eq!(name_to_text(&name(vec![b"a\\b", b"example"])), "a\\\\b.example");
+    }
+
+    #[test]
+    fn soa_rname_email_unescapes_the_local_part() {
+        assert_eq!(soa_rname_email("hostmaster.example.com").as_deref(), Some("hostmaster@example.com"));
+        assert_eq!(soa_rname_email("john\\.doe.example.com").as_deref(), Some("john.doe@example.com"));
+        assert_eq!(soa_rname_email("john\\046doe.example.com").as_deref(), Some("john.doe@example.com"));
+        assert_eq!(soa_rname_email("a\\\\b.example.com").as_deref(), Some("a\\b@example.com"));
+        assert_eq!(soa_rname_email("john\\999.example.com"), None);
+        assert_eq!(soa_rname_email("john\\"), None);
+        assert_eq!(soa_rname_email("localhost"), None);
+        assert_eq!(soa_rname_email(".example.com"), None);
+    }
+
+    #[test]
+    fn sort_addresses_orders_numerically() {
+        let mut addresses: Vec<String> = ["10.0.0.1", "not-an-ip", "2001:db8::1", "9.0.0.1", "::1", "192.0.2.1"]
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        sort_addresses(&mut addresses);
+        assert_eq!(addresses, ["9.0.0.1", "10.0.0.1", "192.0.2.1", "::1", "2001:db8::1", "not-an-ip"]);
+    }
+
+    #[test]
+    fn format_ip_addr_scopes_only_link_local_addresses() {
+        let link_local = IpAddr::from_str("fe80::1").unwrap();
+        let global = IpAddr::from_str("2001:db8::1").unwrap();
+        let ipv4 = IpAddr::from_str("192.0.2.1").unwrap();
+
+        // fe80::/10 ends at febf::, fec0:: is outside it
+        let link_local_end = IpAddr::from_str("febf::1").unwrap();
+        let site_local = IpAddr::from_str("fec0::1").unwrap();
+
+        GLOBAL_DNS_STATE.set_dns_ipv6_zone_id(Some("eth0".to_string())).unwrap();
+        let scoped: Vec<String> = [link_local, link_local_end, site_local, global, ipv4].iter().map(format_ip_addr).collect();
+        GLOBAL_DNS_STATE.set_dns_ipv6_zone_id(None).unwrap();
+
+        assert_eq!(scoped, ["fe80::1%eth0", "febf::1%eth0", "fec0::1", "2001:db8::1", "192.0.2.1"]);
+        assert_eq!(format_ip_addr(&link_local), "fe80::1");
+    }
+
+    #[test]
+    fn system_config_or_default_falls_back_to_the_servers() {
+        let servers = |config: &ResolverConfig| -> Vec<SocketAddr> {
+            config.name_servers().iter().map(|server| server.socket_addr).collect()
+        };
+
+        let (config, reason) = system_config_or_default::<ResolveError>(Ok(ResolverConfig::new()));
+        assert_eq!(servers(&config), servers(&ResolverConfig::default()));
+        assert_eq!(reason.as_deref(), Some("no name servers configured"));
+
+        let (config, reason) = system_config_or_default(Err("resolv.conf not found"));
+        assert_eq!(servers(&config), servers(&ResolverConfig::default()));
+        assert_eq!(reason.as_deref(), Some("resolv.conf not found"));
+
+        let (config, reason) = system_config_or_default::<ResolveError>(Ok(ResolverConfig::quad9()));
+        assert_eq!(servers(&config), servers(&ResolverConfig::quad9()));
+        assert_eq!(reason, None);
+    }
+
+    #[test]
+    fn parse_resolver_spec_binds_only_local_addresses() {
+        let (config, _) = parse_resolver_spec("127.0.0.1:5353;bind=127.0.0.1").unwrap();
+        let bind_addrs: Vec<_> = config.name_servers().iter().map(|server| server.bind_addr).collect();
+        assert!(!bind_addrs.is_empty());
+        assert!(bind_addrs.iter().all(|addr| *addr == Some(SocketAddr::from(([127, 0, 0, 1], 0)))));
+
+        let error = parse_resolver_spec("8.8.8.8;bind=192.0.2.1").unwrap_err().to_string();
+        assert!(error.starts_with("Invalid resolver spec '8.8.8.8;bind=192.0.2.1': cannot bind to '192.0.2.1'"), "{}", error);
+    }
+
+    #[test]
+    fn names_are_normalized_to_lowercase() {
+        assert_eq!(answer_name(&Name::from_str("Dns.GOOGLE.").unwrap()), "dns.google");
+        assert_eq!(answer_name(&Name::from_str("XN--MNCHEN-3YA.De.").unwrap()), "münchen.de");
+        assert_eq!(to_ascii_hostname("MÜNCHEN.de").unwrap(), "xn--mnchen-3ya.de");
+        assert_eq!(to_ascii_hostname("Example.COM").unwrap(), "example.com");
+    }
+
+    #[test]
+    fn cidr_host_addresses_enforces_a_hard_limit() {
+        let cidr = |text: &str| parse_cidr(text).unwrap();
+        assert_eq!(cidr_host_addresses(&cidr("192.0.2.0/30"), 16).unwrap().len(), 2);
+        assert_eq!(cidr_host_addresses(&cidr("10.0.0.0/12"), 12).unwrap().len(), (1 << 20) - 2);
+
+        for network in ["10.0.0.0/11", "0.0.0.0/0", "2001:db8::/64", "::/0"] {
+            let error = cidr_host_addresses(&cidr(network), 0).unwrap_err().to_string();
+            assert!(error.ends_with("holds more than the maximum of 1048576 addresses"), "{}: {}", network, error);
+        }
+        assert!(cidr_host_addresses(&cidr("10.0.0.0/15"), 16).is_err());
+    }
+}